@@ -49,7 +49,10 @@ pub async fn parse_courses_and_compute_degree_status(
 
     degree_status.fill_tags(&courses);
 
-    degree_status.compute(catalog, course::vec_to_map(courses));
+    if let Err(e) = degree_status.compute(catalog, course::vec_to_map(courses)) {
+        Catalog::flag_broken(&db, &e.catalog_id, None, e.error.to_string()).await;
+        return Err(e.into());
+    }
 
     Ok(HttpResponse::Ok().json(degree_status))
 }
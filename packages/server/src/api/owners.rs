@@ -1,12 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-use crate::core::catalog_validations;
-use crate::db::Db;
+use crate::core::credit_transfer_graph::CreditTransferGraph;
+use crate::core::{catalog_changes::CatalogChanges, catalog_diff, catalog_validations};
+use crate::db::{Db, FilterOption};
 use crate::error::AppError;
-use crate::resources::catalog::Catalog;
-use crate::resources::{course::Course, user::User};
-use actix_web::web::{Data, Json, Path};
-use actix_web::{delete, get, put, HttpResponse};
+use crate::resources::catalog_version::{BrokenCatalogVersion, CatalogVersion, VersionState};
+use crate::resources::{catalog::Catalog, catalog_file};
+use crate::resources::{course::Course, user::User, version::DataVersion};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, post, put, HttpRequest, HttpResponse};
+use bson::DateTime;
+use serde::Deserialize;
 
 /////////////////////////////////////////////////////////////////////////////
 // Course API
@@ -37,9 +42,9 @@ pub async fn create_or_update_course(
     course: Json<Course>,
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
-    db.create_or_update::<Course>(course.into_inner())
-        .await
-        .map(|course| HttpResponse::Ok().json(course))
+    let course = db.create_or_update::<Course>(course.into_inner()).await?;
+    DataVersion::bump(&db, DataVersion::COURSES).await?;
+    Ok(HttpResponse::Ok().json(course))
 }
 
 #[delete("/courses/{id}")]
@@ -48,15 +53,22 @@ pub async fn delete_course(
     id: Path<String>,
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
-    db.delete::<Course>(id.as_str())
-        .await
-        .map(|_| HttpResponse::Ok().finish())
+    db.delete::<Course>(id.as_str()).await?;
+    DataVersion::bump(&db, DataVersion::COURSES).await?;
+    Ok(HttpResponse::Ok().finish())
 }
 
 /////////////////////////////////////////////////////////////////////////////
 // Catalog API
 /////////////////////////////////////////////////////////////////////////////
 
+#[get("/catalogs")]
+pub async fn get_all_catalogs(_: User, db: Data<Db>) -> Result<HttpResponse, AppError> {
+    db.get_all::<Catalog>()
+        .await
+        .map(|catalogs| HttpResponse::Ok().json(catalogs))
+}
+
 #[get("/catalogs/{id}")]
 pub async fn get_catalog_by_id(
     _: User,
@@ -69,15 +81,334 @@ pub async fn get_catalog_by_id(
         .map(|course| HttpResponse::Ok().json(course))
 }
 
+// Returns all the problems which were found in the catalog, including warnings
+#[get("/catalogs/{id}/validation")]
+pub async fn get_catalog_validation(
+    _: User,
+    id: Path<String>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let catalog = db.get::<Catalog>(&obj_id).await?;
+    let courses = db.get_all::<Course>().await?;
+    Ok(HttpResponse::Ok().json(catalog_validations::catalog_report(
+        &catalog,
+        Some(&courses),
+    )))
+}
+
+// Returns the course banks of the catalog and the credit overflows between them, as JSON or as Graphviz DOT with "format=dot".
+// With "user_id", the overflows are annotated with what was transferred along them in the user's degree status
+#[get("/catalogs/{id}/graph")]
+pub async fn get_credit_transfer_graph(
+    _: User,
+    id: Path<String>,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let params = Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let catalog = db.get::<Catalog>(&obj_id).await?;
+    let mut graph = CreditTransferGraph::new(&catalog)?;
+
+    if let Some(user_id) = params.get("user_id") {
+        let user = db.get::<User>(user_id).await?;
+        if user.details.catalog.as_ref().map(|catalog| catalog.id) != Some(obj_id) {
+            return Err(AppError::BadRequest(
+                "The degree status of the user was not computed against this catalog".into(),
+            ));
+        }
+        graph.annotate(&user.details.degree_status);
+    }
+
+    match params.get("format").map(String::as_str) {
+        Some("dot") => Ok(HttpResponse::Ok()
+            .content_type("text/vnd.graphviz")
+            .body(graph.to_dot())),
+        Some("json") | None => Ok(HttpResponse::Ok().json(graph)),
+        Some(format) => Err(AppError::BadRequest(format!("Unknown format {format}"))),
+    }
+}
+
+async fn get_catalog_versions(
+    db: &Db,
+    catalog_id: &bson::oid::ObjectId,
+) -> Result<Vec<CatalogVersion>, AppError> {
+    let mut versions = db
+        .get_filtered::<CatalogVersion>(FilterOption::In, "catalog_id", vec![*catalog_id])
+        .await?;
+    versions.sort_by_key(|version| version.version);
+    Ok(versions)
+}
+
+// Saves the catalog as the draft version of the catalog, replacing the current draft if there is one.
+// Only the draft endpoint may replace a draft, the other ways of saving a catalog fail while there is one
+async fn save_draft(
+    db: &Db,
+    catalog: Catalog,
+    changelog: String,
+    author: &str,
+) -> Result<CatalogVersion, AppError> {
+    let version = match get_catalog_versions(db, &catalog.id).await?.last() {
+        Some(last_version) if !last_version.is_published() => last_version.version,
+        Some(last_version) => last_version.version + 1,
+        None => 1,
+    };
+    db.update::<CatalogVersion>(CatalogVersion {
+        id: CatalogVersion::id_for(&catalog.id, version),
+        catalog_id: catalog.id,
+        version,
+        state: VersionState::Draft,
+        changelog,
+        author: author.to_string(),
+        timestamp: DateTime::now(),
+        catalog,
+    })
+    .await
+}
+
+// Publishes a draft version, which becomes the catalog students see
+async fn publish(db: &Db, mut version: CatalogVersion) -> Result<Catalog, AppError> {
+    if version.is_published() {
+        return Err(AppError::BadRequest(format!(
+            "Version {} is already published and can't be changed",
+            version.version
+        )));
+    }
+    let courses = db.get_all::<Course>().await?;
+    catalog_validations::catalog_report(&version.catalog, Some(&courses)).into_result()?;
+
+    version.state = VersionState::Published;
+    version.timestamp = DateTime::now();
+    version.catalog.broken = None;
+    let version = db.update::<CatalogVersion>(version).await?;
+    let catalog = db.update::<Catalog>(version.catalog).await?;
+    DataVersion::bump(db, catalog.id.to_hex()).await?;
+    Ok(catalog)
+}
+
+// Validates the catalog and publishes it as a new version of the catalog with the given id, with the "changelog" query param.
+// Fails with a conflict while the catalog has an unpublished draft.
+// With "validate_only=true" the catalog is only validated, and the validation report is returned instead of the catalog
+async fn save_catalog(
+    mut catalog: Catalog,
+    id: &str,
+    author: &str,
+    req: &HttpRequest,
+    db: &Db,
+) -> Result<HttpResponse, AppError> {
+    let params = Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let validate_only = params
+        .get("validate_only")
+        .map(|value| value == "true")
+        .unwrap_or(false);
+
+    catalog.id = bson::oid::ObjectId::from_str(id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let courses = db.get_all::<Course>().await?;
+    let report = catalog_validations::catalog_report(&catalog, Some(&courses));
+    if validate_only {
+        return Ok(HttpResponse::Ok().json(report));
+    }
+    report.into_result()?;
+
+    // Publishing over the draft would lose the owner's changes in it
+    if let Some(draft) = get_catalog_versions(db, &catalog.id)
+        .await?
+        .into_iter()
+        .find(|version| !version.is_published())
+    {
+        return Err(AppError::Conflict(format!(
+            "Version {} of the catalog is an unpublished draft, publish it or update it instead",
+            draft.version
+        )));
+    }
+
+    let changelog = params.get("changelog").cloned().unwrap_or_default();
+    let version = save_draft(db, catalog, changelog, author).await?;
+    let catalog = publish(db, version).await?;
+    Ok(HttpResponse::Ok().json(catalog))
+}
+
 #[put("/catalogs/{id}")]
 pub async fn create_or_update_catalog(
-    _: User,
-    _id: Path<String>,
+    user: User,
+    id: Path<String>,
     catalog: Json<Catalog>,
+    req: HttpRequest,
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
-    catalog_validations::validate_catalog(&catalog)?;
-    db.create_or_update::<Catalog>(catalog.into_inner())
-        .await
-        .map(|catalog| HttpResponse::Ok().json(catalog))
+    save_catalog(catalog.into_inner(), &id, &user.sub, &req, &db).await
+}
+
+// Creates a new catalog from the catalog with the given id, adapted by the TOML change file in the body.
+// Supports the same query params as "create_or_update_catalog"
+#[post("/catalogs/{id}/clone")]
+pub async fn clone_catalog(
+    user: User,
+    id: Path<String>,
+    changes: String,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let catalog = db.get::<Catalog>(&obj_id).await?;
+    let catalog = CatalogChanges::from_toml(&changes)?.apply(&catalog)?;
+    let new_id = catalog.id.to_hex();
+    save_catalog(catalog, &new_id, &user.sub, &req, &db).await
+}
+
+#[get("/catalogs/{id}/versions")]
+pub async fn get_versions(
+    _: User,
+    id: Path<String>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let mut versions = get_catalog_versions(&db, &obj_id).await?;
+    // Broken versions are recorded apart from the versions, which are immutable once published
+    let broken_versions = db
+        .get_filtered::<BrokenCatalogVersion>(FilterOption::In, "catalog_id", vec![obj_id])
+        .await?;
+    for version in versions.iter_mut() {
+        if let Some(broken_version) = broken_versions
+            .iter()
+            .find(|broken_version| broken_version.id == version.id)
+        {
+            version.catalog.broken = Some(broken_version.reason.clone());
+        }
+    }
+    Ok(HttpResponse::Ok().json(versions))
+}
+
+#[derive(Deserialize)]
+pub struct DraftPayload {
+    pub catalog: Catalog,
+    #[serde(default)]
+    pub changelog: String,
+}
+
+// Drafts are not validated until they are published
+#[put("/catalogs/{id}/versions/draft")]
+pub async fn save_draft_version(
+    user: User,
+    id: Path<String>,
+    draft: Json<DraftPayload>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let DraftPayload {
+        mut catalog,
+        changelog,
+    } = draft.into_inner();
+    catalog.id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let version = save_draft(&db, catalog, changelog, &user.sub).await?;
+    Ok(HttpResponse::Ok().json(version))
+}
+
+#[post("/catalogs/{id}/versions/{version}/publish")]
+pub async fn publish_version(
+    _: User,
+    path: Path<(String, u32)>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let (id, version) = path.into_inner();
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let version = db
+        .get::<CatalogVersion>(CatalogVersion::id_for(&obj_id, version))
+        .await?;
+    let catalog = publish(&db, version).await?;
+    Ok(HttpResponse::Ok().json(catalog))
+}
+
+// Returns the structural changes from version "from" to version "to"
+#[get("/catalogs/{id}/versions/diff")]
+pub async fn get_versions_diff(
+    _: User,
+    id: Path<String>,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let params = Query::<HashMap<String, u32>>::from_query(req.query_string())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let (Some(from), Some(to)) = (params.get("from"), params.get("to")) else {
+        return Err(AppError::BadRequest("Missing query params".into()));
+    };
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let from = db
+        .get::<CatalogVersion>(CatalogVersion::id_for(&obj_id, *from))
+        .await?;
+    let to = db
+        .get::<CatalogVersion>(CatalogVersion::id_for(&obj_id, *to))
+        .await?;
+    Ok(HttpResponse::Ok().json(catalog_diff::diff(&from.catalog, &to.catalog)))
+}
+
+// Returns the catalog in the TOML catalog file format
+#[get("/catalogs/{id}/export")]
+pub async fn export_catalog(
+    _: User,
+    id: Path<String>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let catalog = db.get::<Catalog>(&obj_id).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/toml")
+        .body(catalog_file::catalog_to_toml(catalog)?))
+}
+
+// Same as "create_or_update_catalog", for a catalog in the TOML catalog file format
+#[put("/catalogs/{id}/import")]
+pub async fn import_catalog(
+    user: User,
+    id: Path<String>,
+    toml: String,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    save_catalog(
+        catalog_file::catalog_from_toml(&toml)?,
+        &id,
+        &user.sub,
+        &req,
+        &db,
+    )
+    .await
+}
+
+#[delete("/catalogs/{id}")]
+pub async fn delete_catalog(
+    _: User,
+    id: Path<String>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    // A pinned version is always a version of the user's primary catalog
+    let mut referencing_users = HashSet::new();
+    for field in [
+        "details.catalog._id",
+        "details.secondary_catalogs.catalog._id",
+    ] {
+        referencing_users.extend(
+            db.get_filtered::<User>(FilterOption::In, field, vec![obj_id])
+                .await?
+                .into_iter()
+                .map(|user| user.sub),
+        );
+    }
+    if !referencing_users.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "Catalog {} is still used by {} users",
+            obj_id,
+            referencing_users.len()
+        )));
+    }
+    for version in get_catalog_versions(&db, &obj_id).await? {
+        db.delete::<BrokenCatalogVersion>(&version.id).await?;
+        db.delete::<CatalogVersion>(version.id).await?;
+    }
+    db.delete::<Catalog>(&obj_id).await?;
+    DataVersion::bump(&db, obj_id.to_hex()).await?;
+    Ok(HttpResponse::Ok().finish())
 }
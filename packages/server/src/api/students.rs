@@ -1,21 +1,33 @@
 use std::{collections::HashMap, str::FromStr};
 
 use actix_web::{
-    get, post, put,
-    web::{Data, Json, Query},
+    get,
+    http::header::ACCEPT_LANGUAGE,
+    post, put,
+    web::{Data, Json, Path, Query},
     HttpMessage, HttpRequest, HttpResponse,
 };
 use bson::DateTime;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    core::{degree_status::DegreeStatus, parser},
+    consts,
+    core::{
+        catalog_validations,
+        degree_status::DegreeStatus,
+        locale::{self, Language},
+        parser,
+    },
     db::{Db, FilterOption},
     error::AppError,
     middleware::auth::Sub,
     resources::{
-        catalog::{Catalog, DisplayCatalog},
+        catalog::{Catalog, CatalogDetails, CourseSharing, DisplayCatalog, SecondaryCatalog},
+        catalog_version::CatalogVersion,
         course::{self, Course, CourseId},
+        snapshot::DegreeStatusSnapshot,
         user::{User, UserDetails, UserSettings},
+        version::DataVersion,
     },
 };
 
@@ -28,6 +40,14 @@ pub async fn get_catalogs(
     let params = Query::<HashMap<String, String>>::from_query(req.query_string())
         .map_err(|e| AppError::BadRequest(e.to_string()))?;
     let catalogs = match params.iter().last() {
+        // The structured year and track fields are numbers, and are matched exactly
+        Some((key, value)) if ["start_year", "track_years"].contains(&key.as_str()) => {
+            let value = value
+                .parse::<i64>()
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            db.get_filtered::<Catalog>(FilterOption::In, key, vec![value])
+                .await
+        }
         Some((key, value)) => {
             db.get_filtered::<Catalog>(FilterOption::Regex, key, value)
                 .await
@@ -42,6 +62,102 @@ pub async fn get_catalogs(
     ))
 }
 
+// Returns the structure of a catalog: its banks with their rules and courses, and the credit overflows between them
+#[get("/catalogs/{id}")]
+pub async fn get_catalog_details(
+    _: User,
+    id: Path<String>,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let obj_id = bson::oid::ObjectId::from_str(&id).map_err(|e| AppError::Bson(e.to_string()))?;
+    let catalog = db.get::<Catalog>(&obj_id).await?;
+    let courses = db
+        .get_filtered::<Course>(FilterOption::In, "_id", catalog.get_all_course_ids())
+        .await?;
+    Ok(HttpResponse::Ok().json(CatalogDetails::new(catalog, &course::vec_to_map(courses))))
+}
+
+// Compares the user's status against several catalogs, without changing the user's own status
+#[post("/catalogs/compare")]
+pub async fn compare_catalogs(
+    user: User,
+    catalog_ids: Json<Vec<bson::oid::ObjectId>>,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let language = response_language(&user, &req);
+    let catalog_ids = catalog_ids.into_inner();
+    if catalog_ids.len() < 2 {
+        return Err(AppError::BadRequest(
+            "At least two catalogs are required for a comparison".into(),
+        ));
+    }
+    let mut catalogs = Vec::new();
+    for catalog_id in catalog_ids.iter() {
+        catalogs.push(db.get::<Catalog>(catalog_id).await?);
+    }
+
+    let mut degree_status = user.details.degree_status;
+    let courses = db
+        .get_filtered::<Course>(
+            FilterOption::In,
+            "_id",
+            catalogs
+                .iter()
+                .flat_map(|catalog| catalog.get_all_course_ids())
+                .chain(
+                    degree_status
+                        .course_statuses
+                        .iter()
+                        .map(|cs| cs.course.id.clone()),
+                )
+                .collect::<Vec<CourseId>>(),
+        )
+        .await?;
+    degree_status.fill_tags(&courses);
+
+    let mut comparison = match degree_status.compare_catalogs(catalogs, course::vec_to_map(courses))
+    {
+        Ok(comparison) => comparison,
+        Err(e) => {
+            Catalog::flag_broken(&db, &e.catalog_id, None, e.error.to_string()).await;
+            return Err(e.into());
+        }
+    };
+    comparison
+        .statuses
+        .iter_mut()
+        .for_each(|catalog_status| catalog_status.degree_status.localize(language));
+    localized_response(&comparison, language)
+}
+
+// The language of the response is the user's chosen language, or the language which the client asked for
+fn response_language(user: &User, req: &HttpRequest) -> Language {
+    user.settings
+        .language
+        .or_else(|| {
+            req.headers()
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|header| header.to_str().ok())
+                .and_then(Language::from_accept_language)
+        })
+        .unwrap_or_default()
+}
+
+fn localized_response(
+    value: &impl Serialize,
+    language: Language,
+) -> Result<HttpResponse, AppError> {
+    let value = locale::serialize_in(language, || serde_json::to_value(value))
+        .map_err(|e| AppError::InternalServer(e.to_string()))?;
+    Ok(HttpResponse::Ok().json(value))
+}
+
+fn localized_user_response(mut user: User, language: Language) -> Result<HttpResponse, AppError> {
+    user.details.degree_status.localize(language);
+    localized_response(&user, language)
+}
+
 //TODO: maybe this should be "PUT" because it will ALWAYS create a user if one doesn't exist?
 #[get("/login")]
 pub async fn login(db: Data<Db>, req: HttpRequest) -> Result<HttpResponse, AppError> {
@@ -74,34 +190,98 @@ pub async fn login(db: Data<Db>, req: HttpRequest) -> Result<HttpResponse, AppEr
     // Don't send the user's last seen time to the client
     updated_user.last_seen = None;
 
-    Ok(HttpResponse::Ok().json(updated_user))
+    let language = response_language(&updated_user, &req);
+    localized_user_response(updated_user, language)
 }
 
 #[put("/catalog")]
 pub async fn update_catalog(
     mut user: User,
     catalog_id: String,
+    req: HttpRequest,
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
     let obj_id = bson::oid::ObjectId::from_str(&catalog_id)?;
     let catalog = db.get::<Catalog>(&obj_id).await?;
+    // A corrupted catalog can't be used for computing the degree status
+    catalog_validations::validate_computable_catalog(&catalog)?;
     user.details.catalog = Some(DisplayCatalog::from(catalog));
+    user.details.catalog_version = None;
     user.details.modified = true;
 
     // Updating the catalog renders the current course types invalid in the new catalog's context,
     // so we need to clear them out and let the algorithm recompute them
-    user.details
-        .degree_status
-        .course_statuses
-        .iter_mut()
-        .for_each(|cs| {
-            cs.r#type = None;
-            cs.specialization_group_name = None;
-            cs.additional_msg = None;
+    user.details.degree_status.clear_types();
+
+    let updated_user = db.update::<User>(user).await?;
+    let language = response_language(&updated_user, &req);
+    localized_user_response(updated_user, language)
+}
+
+// Pins a published version of the user's catalog, or follows the latest version if the version is null
+#[put("/catalog-version")]
+pub async fn update_catalog_version(
+    mut user: User,
+    version: Json<Option<u32>>,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let catalog_id = user
+        .details
+        .catalog
+        .as_ref()
+        .ok_or_else(|| AppError::BadRequest("No catalog chosen for user".into()))?
+        .id;
+    let version = version.into_inner();
+    if let Some(version) = version {
+        let catalog_version = db
+            .get::<CatalogVersion>(CatalogVersion::id_for(&catalog_id, version))
+            .await?;
+        if !catalog_version.is_published() {
+            return Err(AppError::BadRequest(format!(
+                "Version {version} of the catalog is not published"
+            )));
+        }
+    }
+    user.details.catalog_version = version;
+    user.details.modified = true;
+
+    let updated_user = db.update::<User>(user).await?;
+    let language = response_language(&updated_user, &req);
+    localized_user_response(updated_user, language)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondaryCatalogPayload {
+    pub catalog_id: bson::oid::ObjectId,
+    #[serde(default)]
+    pub course_sharing: CourseSharing,
+}
+
+#[put("/secondary-catalogs")]
+pub async fn update_secondary_catalogs(
+    mut user: User,
+    payload: Json<Vec<SecondaryCatalogPayload>>,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let mut secondary_catalogs = Vec::new();
+    for secondary_catalog in payload.into_inner() {
+        let catalog = db.get::<Catalog>(&secondary_catalog.catalog_id).await?;
+        // A corrupted secondary catalog fails the computation of the whole degree status
+        catalog_validations::validate_computable_catalog(&catalog)?;
+        secondary_catalogs.push(SecondaryCatalog {
+            catalog: DisplayCatalog::from(catalog),
+            course_sharing: secondary_catalog.course_sharing,
         });
+    }
+    user.details.secondary_catalogs = secondary_catalogs;
+    user.details.modified = true;
 
     let updated_user = db.update::<User>(user).await?;
-    Ok(HttpResponse::Ok().json(updated_user))
+    let language = response_language(&updated_user, &req);
+    localized_user_response(updated_user, language)
 }
 
 #[get("/courses")]
@@ -134,18 +314,25 @@ pub async fn get_courses_by_filter(
 pub async fn add_courses(
     mut user: User,
     data: String,
+    req: HttpRequest,
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
     user.details.degree_status = DegreeStatus::default();
     user.details.degree_status.course_statuses = parser::parse_copy_paste_data(&data)?;
     user.details.modified = true;
     let updated_user = db.update::<User>(user).await?;
-    Ok(HttpResponse::Ok().json(updated_user))
+    let language = response_language(&updated_user, &req);
+    localized_user_response(updated_user, language)
 }
 
 // here "modified" becomes false
 #[get("/degree-status")]
-pub async fn compute_degree_status(mut user: User, db: Data<Db>) -> Result<HttpResponse, AppError> {
+pub async fn compute_degree_status(
+    mut user: User,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let language = response_language(&user, &req);
     let catalog_id = user
         .details
         .catalog
@@ -153,7 +340,24 @@ pub async fn compute_degree_status(mut user: User, db: Data<Db>) -> Result<HttpR
         .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?
         .id;
 
-    let catalog = db.get::<Catalog>(&catalog_id).await?;
+    // The degree status is recomputed only if the user's courses or the data it depends on changed
+    let data_versions = get_data_versions(&db, &user).await?;
+    if !user.details.modified
+        && user.details.compute_cache_key.as_deref()
+            == Some(compute_cache_key(&user, &catalog_id, &data_versions).as_str())
+    {
+        return localized_user_response(user, language);
+    }
+
+    let catalog = get_user_catalog(&db, &user, &catalog_id).await?;
+
+    let mut secondary_catalogs = Vec::new();
+    for secondary_catalog in user.details.secondary_catalogs.iter() {
+        secondary_catalogs.push((
+            db.get::<Catalog>(&secondary_catalog.catalog.id).await?,
+            secondary_catalog.course_sharing.clone(),
+        ));
+    }
 
     user.details.modified = false;
 
@@ -164,6 +368,11 @@ pub async fn compute_degree_status(mut user: User, db: Data<Db>) -> Result<HttpR
             catalog
                 .get_all_course_ids()
                 .into_iter()
+                .chain(
+                    secondary_catalogs
+                        .iter()
+                        .flat_map(|(secondary_catalog, _)| secondary_catalog.get_all_course_ids()),
+                )
                 .chain(
                     user.details
                         .degree_status
@@ -181,16 +390,153 @@ pub async fn compute_degree_status(mut user: User, db: Data<Db>) -> Result<HttpR
     if user.details.compute_in_progress {
         course_list = user.details.degree_status.set_in_progress_to_complete();
     }
+    let input_hash = user.details.degree_status.input_hash(&catalog_id);
 
-    user.details
-        .degree_status
-        .compute(catalog, course::vec_to_map(courses));
+    // A status which was computed against a corrupted catalog is misleading, so it is not saved
+    if let Err(e) = user.details.degree_status.compute_with_secondary_catalogs(
+        catalog,
+        secondary_catalogs,
+        course::vec_to_map(courses),
+    ) {
+        let version = if e.catalog_id == catalog_id {
+            user.details.catalog_version
+        } else {
+            None
+        };
+        Catalog::flag_broken(&db, &e.catalog_id, version, e.error.to_string()).await;
+        return Err(e.into());
+    }
 
     if user.details.compute_in_progress {
         user.details.degree_status.set_to_in_progress(course_list);
     }
+    user.details.compute_cache_key = Some(compute_cache_key(&user, &catalog_id, &data_versions));
     db.update::<User>(user.clone()).await?;
-    Ok(HttpResponse::Ok().json(user))
+    record_degree_status_snapshot(&db, &user, catalog_id, input_hash).await?;
+    localized_user_response(user, language)
+}
+
+// Returns the version of the catalog which the user pinned, or the latest version if the user didn't pin a version
+async fn get_user_catalog(
+    db: &Db,
+    user: &User,
+    catalog_id: &bson::oid::ObjectId,
+) -> Result<Catalog, AppError> {
+    match user.details.catalog_version {
+        Some(version) => db
+            .get::<CatalogVersion>(CatalogVersion::id_for(catalog_id, version))
+            .await
+            .map(|catalog_version| catalog_version.catalog),
+        None => db.get::<Catalog>(catalog_id).await,
+    }
+}
+
+async fn get_data_versions(db: &Db, user: &User) -> Result<String, AppError> {
+    let mut data_versions = vec![format!(
+        "{}:{}",
+        DataVersion::COURSES,
+        DataVersion::get(db, DataVersion::COURSES).await?
+    )];
+    let catalog_ids = user
+        .details
+        .catalog
+        .iter()
+        .chain(
+            user.details
+                .secondary_catalogs
+                .iter()
+                .map(|secondary_catalog| &secondary_catalog.catalog),
+        )
+        .map(|catalog| catalog.id.to_hex());
+    for catalog_id in catalog_ids {
+        let version = DataVersion::get(db, catalog_id.clone()).await?;
+        data_versions.push(format!("{catalog_id}:{version}"));
+    }
+    Ok(data_versions.join(","))
+}
+
+fn compute_cache_key(user: &User, catalog_id: &bson::oid::ObjectId, data_versions: &str) -> String {
+    format!(
+        "{}|{:?}|{}|{}",
+        data_versions,
+        user.details.catalog_version,
+        user.details.compute_in_progress,
+        user.details.degree_status.input_hash(catalog_id)
+    )
+}
+
+async fn get_degree_status_history(
+    db: &Db,
+    user: &User,
+) -> Result<Vec<DegreeStatusSnapshot>, AppError> {
+    let mut history = db
+        .get_filtered::<DegreeStatusSnapshot>(FilterOption::In, "user_id", vec![user.sub.clone()])
+        .await?;
+    history.sort_by_key(|snapshot| snapshot.timestamp);
+    Ok(history)
+}
+
+// Keeps the last computed degree statuses of the user, a snapshot is recorded only if the status changed
+async fn record_degree_status_snapshot(
+    db: &Db,
+    user: &User,
+    catalog_id: bson::oid::ObjectId,
+    input_hash: String,
+) -> Result<(), AppError> {
+    let history = get_degree_status_history(db, user).await?;
+    if let Some(last_snapshot) = history.last() {
+        if last_snapshot.input_hash == input_hash
+            && serde_json::to_value(&last_snapshot.degree_status).ok()
+                == serde_json::to_value(&user.details.degree_status).ok()
+        {
+            return Ok(());
+        }
+    }
+
+    db.update::<DegreeStatusSnapshot>(DegreeStatusSnapshot {
+        id: bson::oid::ObjectId::new(),
+        user_id: user.sub.clone(),
+        timestamp: DateTime::now(),
+        catalog_id,
+        input_hash,
+        degree_status: user.details.degree_status.clone(),
+    })
+    .await?;
+
+    // The new snapshot is not part of the history which was fetched before it was recorded
+    let outdated = (history.len() + 1).saturating_sub(consts::DEGREE_STATUS_HISTORY_LIMIT);
+    for snapshot in history.into_iter().take(outdated) {
+        db.delete::<DegreeStatusSnapshot>(snapshot.id).await?;
+    }
+    Ok(())
+}
+
+#[get("/degree-status/history")]
+pub async fn get_history(user: User, db: Data<Db>) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(get_degree_status_history(&db, &user).await?))
+}
+
+#[get("/degree-status/diff")]
+pub async fn get_history_diff(
+    user: User,
+    req: HttpRequest,
+    db: Data<Db>,
+) -> Result<HttpResponse, AppError> {
+    let params = Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let (Some(from), Some(to)) = (params.get("from"), params.get("to")) else {
+        return Err(AppError::BadRequest("Missing query params".into()));
+    };
+    let history = get_degree_status_history(&db, &user).await?;
+    let find_snapshot = |id: &str| -> Result<&DegreeStatusSnapshot, AppError> {
+        let id = bson::oid::ObjectId::from_str(id)?;
+        history
+            .iter()
+            .find(|snapshot| snapshot.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Degree status snapshot: {id}")))
+    };
+    let (from, to) = (find_snapshot(from)?, find_snapshot(to)?);
+    Ok(HttpResponse::Ok().json(from.degree_status.diff(&to.degree_status)))
 }
 
 // here "modified" is true
@@ -201,6 +547,8 @@ pub async fn update_details(
     db: Data<Db>,
 ) -> Result<HttpResponse, AppError> {
     user.details = details.into_inner();
+    // The client might send back the status in the user's language, but it is always stored in Hebrew
+    user.details.degree_status.localize(Language::Hebrew);
     db.update::<User>(user).await?;
     Ok(HttpResponse::Ok().finish())
 }
@@ -5,13 +5,18 @@ use crate::{
         owners,
         students::{self, login},
     },
-    core::{degree_status::DegreeStatus, messages},
-    db::Db,
+    core::{
+        catalog_diff::CatalogDiff, catalog_validations::CatalogReport, degree_status::DegreeStatus,
+        messages, types::CreditOverflow,
+    },
+    db::{Db, FilterOption},
     middleware::{self, auth},
     resources::{
-        catalog::{Catalog, DisplayCatalog},
+        catalog::{Catalog, CatalogDetails, DisplayCatalog},
+        catalog_version::{CatalogVersion, VersionState},
         course::{Course, CourseStatus},
         user::{Permissions, User, UserDetails},
+        version::DataVersion,
     },
 };
 use actix_rt::test;
@@ -38,14 +43,18 @@ pub async fn test_get_all_catalogs() {
             .app_data(Data::new(Permissions::Student))
             .app_data(auth::JwtDecoder::new_with_parser(parser))
             .wrap(from_fn(middleware::auth::authenticate))
-            .service(scope("/students").service(students::get_catalogs)),
+            .service(
+                scope("/students")
+                    .service(students::get_catalogs)
+                    .service(students::get_catalog_details),
+            ),
     )
     .await;
 
     // Create and send request
     let resp = test::TestRequest::get()
         .uri("/students/catalogs")
-        .insert_header(("authorization", jwt))
+        .insert_header(("authorization", jwt.clone()))
         .send_request(&app)
         .await;
 
@@ -54,6 +63,26 @@ pub async fn test_get_all_catalogs() {
     // Check for valid json response
     let vec_catalogs: Vec<DisplayCatalog> = test::read_body_json(resp).await;
     assert!(vec_catalogs.len() >= 8);
+
+    // The details of a catalog have the same banks as the catalog
+    let resp = test::TestRequest::get()
+        .uri(&format!(
+            "/students/catalogs/{}",
+            vec_catalogs[0].id.to_hex()
+        ))
+        .insert_header(("authorization", jwt))
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+    let catalog_details: CatalogDetails = test::read_body_json(resp).await;
+    assert_eq!(
+        catalog_details
+            .course_banks
+            .iter()
+            .map(|bank| bank.name.clone())
+            .collect::<Vec<_>>(),
+        vec_catalogs[0].course_bank_names
+    );
 }
 
 #[test]
@@ -209,6 +238,46 @@ async fn test_compute_in_progress() {
     assert_eq!(res.status(), StatusCode::OK);
 }
 
+#[test]
+async fn test_cached_degree_status() {
+    // Init env and app
+    let db = Db::new().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(db.clone()))
+            .app_data(Data::new(Permissions::Student))
+            .service(scope("/students").service(students::compute_degree_status)),
+    )
+    .await;
+
+    let get_degree_status = || {
+        let req = test::TestRequest::get()
+            .uri("/students/degree-status")
+            .to_request();
+        req.extensions_mut()
+            .insert::<auth::Sub>("bugo-the-debugo-senior".to_string());
+        req
+    };
+
+    let res = test::call_service(&app, get_degree_status()).await;
+    let user: User = test::read_body_json(res).await;
+    let cache_key = user.details.compute_cache_key.clone();
+    assert!(cache_key.is_some());
+
+    // Nothing changed, so the cached degree status is returned
+    let res = test::call_service(&app, get_degree_status()).await;
+    let cached_user: User = test::read_body_json(res).await;
+    assert_eq!(cached_user.details.compute_cache_key, cache_key);
+
+    // An update of the course data invalidates the cached degree status
+    DataVersion::bump(&db, DataVersion::COURSES)
+        .await
+        .expect("Failed to bump the courses version");
+    let res = test::call_service(&app, get_degree_status()).await;
+    let recomputed_user: User = test::read_body_json(res).await;
+    assert_ne!(recomputed_user.details.compute_cache_key, cache_key);
+}
+
 #[test]
 async fn test_owner_api_courses() {
     // Create authorization header
@@ -295,7 +364,14 @@ async fn test_owner_api_catalogs() {
             .app_data(Data::new(Permissions::Owner))
             .app_data(auth::JwtDecoder::new_with_parser(parser))
             .wrap(from_fn(middleware::auth::authenticate))
-            .service(scope("/owners").service(owners::get_catalog_by_id)),
+            .service(
+                scope("/owners")
+                    .service(owners::get_all_catalogs)
+                    .service(owners::get_catalog_by_id)
+                    .service(owners::get_catalog_validation)
+                    .service(owners::create_or_update_catalog)
+                    .service(owners::delete_catalog),
+            ),
     )
     .await;
 
@@ -309,7 +385,267 @@ async fn test_owner_api_catalogs() {
     let catalog: Catalog = test::read_body_json(res).await;
     assert_eq!(catalog.name, "מדמח הנדסת מחשבים 2018-2019");
 
-    //TODO: create? delete?
+    // get /catalogs
+    let res = test::TestRequest::get()
+        .uri("/owners/catalogs")
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let catalogs: Vec<Catalog> = test::read_body_json(res).await;
+    assert!(catalogs.iter().any(|c| c.id == catalog.id));
+
+    // get /catalogs/{id}/validation
+    let res = test::TestRequest::get()
+        .uri("/owners/catalogs/61ddcc8a2397192f08d517d9/validation")
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let report: CatalogReport = test::read_body_json(res).await;
+    assert!(report.is_valid());
+
+    // put /catalogs/{id}?validate_only=true with a cycle in the credit transfers
+    let new_id = ObjectId::new();
+    let mut corrupted_catalog = catalog.clone();
+    let (first_bank, second_bank) = (
+        corrupted_catalog.course_banks[0].name.clone(),
+        corrupted_catalog.course_banks[1].name.clone(),
+    );
+    corrupted_catalog.credit_overflows.extend([
+        CreditOverflow {
+            from: first_bank.clone(),
+            to: second_bank.clone(),
+            ..Default::default()
+        },
+        CreditOverflow {
+            from: second_bank,
+            to: first_bank,
+            ..Default::default()
+        },
+    ]);
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}?validate_only=true", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(serde_json::to_string(&corrupted_catalog).expect("Fail to serialize catalog"))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let report: CatalogReport = test::read_body_json(res).await;
+    assert!(!report.is_valid());
+
+    // put /catalogs/{id} with the corrupted catalog
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(serde_json::to_string(&corrupted_catalog).expect("Fail to serialize catalog"))
+        .send_request(&app)
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // put /catalogs/{id}
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(serde_json::to_string(&catalog).expect("Fail to serialize catalog"))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let created_catalog: Catalog = test::read_body_json(res).await;
+    assert_eq!(created_catalog.id, new_id);
+    assert_eq!(created_catalog.name, catalog.name);
+
+    // delete /catalogs/{id} of a catalog which a user still uses
+    let user = User {
+        sub: "test_delete_used_catalog".to_string(),
+        details: UserDetails {
+            catalog: Some(DisplayCatalog::from(created_catalog)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    db.update::<User>(user.clone())
+        .await
+        .expect("Failed to create user");
+    let res = test::TestRequest::delete()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    db.delete::<User>(&user.sub)
+        .await
+        .expect("Failed to delete user");
+
+    // delete /catalogs/{id}
+    let res = test::TestRequest::delete()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    // The versions of the catalog are deleted with it
+    assert!(db
+        .get_filtered::<CatalogVersion>(FilterOption::In, "catalog_id", vec![new_id])
+        .await
+        .expect("Failed to get catalog versions")
+        .is_empty());
+
+    // get /catalogs/{id} with 404 error
+    let res = test::TestRequest::get()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+async fn test_catalog_versions() {
+    // Create authorization header
+    let token_claims = jsonwebtoken_google::test_helper::TokenClaims::new();
+    let (jwt, parser, _server) = jsonwebtoken_google::test_helper::setup(&token_claims);
+    // Init env and app
+    let db = Db::new().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(db.clone()))
+            .app_data(Data::new(Permissions::Owner))
+            .app_data(auth::JwtDecoder::new_with_parser(parser))
+            .wrap(from_fn(middleware::auth::authenticate))
+            .service(
+                scope("/owners")
+                    .service(owners::get_catalog_by_id)
+                    .service(owners::create_or_update_catalog)
+                    .service(owners::get_versions)
+                    .service(owners::get_versions_diff)
+                    .service(owners::save_draft_version)
+                    .service(owners::publish_version)
+                    .service(owners::delete_catalog),
+            ),
+    )
+    .await;
+
+    let catalog = db
+        .get::<Catalog>(ObjectId::from_str("61ddcc8a2397192f08d517d9").unwrap())
+        .await
+        .expect("Fail to get catalog");
+    let new_id = ObjectId::new();
+
+    // put /catalogs/{id} publishes the first version
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}?changelog=initial", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(serde_json::to_string(&catalog).expect("Fail to serialize catalog"))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+
+    // put /catalogs/{id}/versions/draft
+    let mut draft = catalog.clone();
+    draft.course_banks[0].credit = Some(1.0);
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}/versions/draft", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(
+            serde_json::json!({ "catalog": draft, "changelog": "less credit" }).to_string(),
+        )
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let version: CatalogVersion = test::read_body_json(res).await;
+    assert_eq!(version.version, 2);
+    assert_eq!(version.state, VersionState::Draft);
+
+    // The draft is not visible to students until it is published
+    let current = db
+        .get::<Catalog>(new_id)
+        .await
+        .expect("Fail to get catalog");
+    assert_eq!(
+        current.course_banks[0].credit,
+        catalog.course_banks[0].credit
+    );
+
+    // The draft is neither published nor replaced by put /catalogs/{id}
+    let res = test::TestRequest::put()
+        .uri(format!("/owners/catalogs/{}?changelog=override", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .insert_header(("content-type", "application/json"))
+        .set_payload(serde_json::to_string(&catalog).expect("Fail to serialize catalog"))
+        .send_request(&app)
+        .await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let current = db
+        .get::<CatalogVersion>(CatalogVersion::id_for(&new_id, 2))
+        .await
+        .expect("Fail to get catalog version");
+    assert_eq!(current.state, VersionState::Draft);
+    assert_eq!(current.catalog.course_banks[0].credit, Some(1.0));
+
+    // post /catalogs/{id}/versions/{version}/publish
+    let res = test::TestRequest::post()
+        .uri(format!("/owners/catalogs/{}/versions/2/publish", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let published: Catalog = test::read_body_json(res).await;
+    assert_eq!(published.course_banks[0].credit, Some(1.0));
+
+    // Published versions are immutable
+    let res = test::TestRequest::post()
+        .uri(format!("/owners/catalogs/{}/versions/2/publish", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // get /catalogs/{id}/versions
+    let res = test::TestRequest::get()
+        .uri(format!("/owners/catalogs/{}/versions", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let versions: Vec<CatalogVersion> = test::read_body_json(res).await;
+    assert_eq!(
+        versions
+            .iter()
+            .map(|version| (version.version, version.changelog.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "initial"), (2, "less credit")]
+    );
+    assert!(versions.iter().all(CatalogVersion::is_published));
+
+    // get /catalogs/{id}/versions/diff
+    let res = test::TestRequest::get()
+        .uri(format!("/owners/catalogs/{}/versions/diff?from=1&to=2", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    let diff: CatalogDiff = test::read_body_json(res).await;
+    assert_eq!(diff.changed_banks.len(), 1);
+    assert!(diff.moved_courses.is_empty());
+
+    // Clean up
+    let res = test::TestRequest::delete()
+        .uri(format!("/owners/catalogs/{}", new_id).as_str())
+        .insert_header(("authorization", jwt.clone()))
+        .send_request(&app)
+        .await;
+    assert!(res.status().is_success());
+    for version in versions {
+        db.delete::<CatalogVersion>(version.id)
+            .await
+            .expect("Fail to delete catalog version");
+    }
 }
 
 #[test]
@@ -399,7 +735,7 @@ async fn test_admins_parse_and_compute_api() {
     assert_eq!(degree_status.total_credit, 106.5);
     assert!(degree_status
         .overflow_msgs
-        .contains(&messages::credit_leftovers_msg(0.0)))
+        .contains(&messages::credit_leftovers_msg(0.0).to_string()))
 }
 
 #[test]
@@ -1,10 +1,13 @@
 pub const ADVANCED_B_COURSES_COUNT_DEMAND: usize = 1;
+pub const DEGREE_STATUS_HISTORY_LIMIT: usize = 20; // Number of degree status snapshots kept for each user
 pub const EXEMPT_COURSES_COUNT_DEMAND: usize = 2;
 pub const MINIMAL_YEAR_FOR_ENGLISH_REQUIREMENT: usize = 2021;
 pub const TECHNICAL_ENGLISH_ADVANCED_B: &str = "324033";
 
 pub mod medicine {
     pub const ALL_BANK_NAME: &str = "חובה";
+    pub const CLINICAL_COURSE_REPETITIONS_LIMIT: usize = 1;
+    pub const CLINICAL_MIN_AVG: f32 = 65.0;
     pub const ELECTIVE_BANK_NAME: &str = "בחירה חופשית";
     pub const FACULTY_ELECTIVE_BANK_NAME: &str = "בחירה פקולטית";
     pub const PRECLINICAL_COURSE_REPETITIONS_LIMIT: usize = 1;
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use crate::core::messages;
+use crate::core::messages::{self, Message};
 use crate::core::types::CreditInfo;
 use crate::resources::course::Course;
 
@@ -11,7 +11,7 @@ impl<'a> BankRuleHandler<'a> {
         // return sum_credit, count_courses, missing_points
         let mut sum_credit = self.credit_overflow;
         let mut count_courses = self.courses_overflow;
-        let mut handled_courses = HashMap::new(); // mapping between the course in the catalog to the course which was taken by the student (relevant for replacements)
+        let mut handled_courses = BTreeMap::new(); // mapping between the course in the catalog to the course which was taken by the student (relevant for replacements)
         self.degree_status
             .course_statuses
             .iter_mut()
@@ -20,8 +20,8 @@ impl<'a> BankRuleHandler<'a> {
                 if self.course_list.contains(&course_status.course.id) {
                     Some((course_status.course.id.clone(), course_status))
                 } else {
-                    let mut find_replacement = |replacements: &HashMap<String, Vec<String>>,
-                                                replacements_msg: fn(&Course) -> String|
+                    let mut find_replacement = |replacements: &BTreeMap<String, Vec<String>>,
+                                                replacements_msg: fn(&Course) -> Message|
                      -> Option<String> {
                         self.course_list.iter().find_map(|course_id| {
                             replacements
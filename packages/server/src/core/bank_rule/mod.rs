@@ -9,7 +9,7 @@ pub mod sport;
 #[cfg(test)]
 pub mod tests;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::resources::{
     catalog::OptionalReplacements,
@@ -25,6 +25,6 @@ pub struct BankRuleHandler<'a> {
     pub courses: &'a HashMap<CourseId, Course>,
     pub credit_overflow: f32,
     pub courses_overflow: usize,
-    pub catalog_replacements: &'a HashMap<CourseId, OptionalReplacements>,
-    pub common_replacements: &'a HashMap<CourseId, OptionalReplacements>,
+    pub catalog_replacements: &'a BTreeMap<CourseId, OptionalReplacements>,
+    pub common_replacements: &'a BTreeMap<CourseId, OptionalReplacements>,
 }
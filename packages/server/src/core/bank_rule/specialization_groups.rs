@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     core::types::{SpecializationGroup, SpecializationGroups},
@@ -11,19 +11,18 @@ use super::BankRuleHandler;
 // sg = specialization_group
 // sgs = specialization_groups
 
-fn get_groups_indices(course_id_to_sg_index: &HashMap<CourseId, usize>) -> Vec<usize> {
-    let mut uniques = HashSet::new();
-    let mut indices = course_id_to_sg_index
-        .clone()
-        .into_values()
-        .collect::<Vec<_>>();
-    indices.retain(|e| uniques.insert(*e));
-    indices
+fn get_groups_indices(course_id_to_sg_index: &BTreeMap<CourseId, usize>) -> Vec<usize> {
+    course_id_to_sg_index
+        .values()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
 }
 
 fn get_complete_sgs_indices(
     sgs: &[SpecializationGroup],
-    course_id_to_sg_index: &HashMap<CourseId, usize>,
+    course_id_to_sg_index: &BTreeMap<CourseId, usize>,
 ) -> Vec<usize> {
     let groups_indices = get_groups_indices(course_id_to_sg_index);
     let mut complete_sgs_indices = Vec::new();
@@ -67,11 +66,11 @@ fn get_complete_sgs_indices(
 fn find_valid_assignment_for_courses(
     sgs: &[SpecializationGroup],
     groups_indices: &[usize],
-    optional_sgs_for_course: &HashMap<CourseId, Vec<usize>>, // list of all optional sgs for each course
-    current_best_match: &mut HashMap<CourseId, usize>,       // the best match of sgs
-    course_id_to_sg_index: &mut HashMap<CourseId, usize>,
+    optional_sgs_for_course: &[(CourseId, Vec<usize>)], // list of all optional sgs for each course
+    current_best_match: &mut BTreeMap<CourseId, usize>, // the best match of sgs
+    course_id_to_sg_index: &mut BTreeMap<CourseId, usize>,
     course_index: usize, // course_index-th element in optional_sgs_for_course
-) -> Option<HashMap<CourseId, usize>> {
+) -> Option<BTreeMap<CourseId, usize>> {
     if course_index >= optional_sgs_for_course.len() {
         let complete_sgs_indices = get_complete_sgs_indices(sgs, course_id_to_sg_index);
         if complete_sgs_indices.len() >= groups_indices.len() {
@@ -84,7 +83,7 @@ fn find_valid_assignment_for_courses(
         }
         return None;
     }
-    if let Some((course_id, optional_groups)) = optional_sgs_for_course.iter().nth(course_index) {
+    if let Some((course_id, optional_groups)) = optional_sgs_for_course.get(course_index) {
         for sg_index in optional_groups {
             course_id_to_sg_index.insert(course_id.clone(), *sg_index);
             if let Some(valid_assignment) = find_valid_assignment_for_courses(
@@ -106,9 +105,9 @@ fn get_sgs_courses_assignment(
     sgs: &[SpecializationGroup],
     groups_indices: &[usize],
     courses: &[CourseId],
-    best_match: &mut HashMap<CourseId, usize>,
-) -> Option<HashMap<CourseId, usize>> {
-    let mut optional_sgs_for_course = HashMap::<CourseId, Vec<usize>>::new();
+    best_match: &mut BTreeMap<CourseId, usize>,
+) -> Option<BTreeMap<CourseId, usize>> {
+    let mut optional_sgs_for_course = Vec::<(CourseId, Vec<usize>)>::new();
     for course_id in courses {
         let mut relevant_groups_for_course = Vec::new();
         for sg_index in groups_indices {
@@ -118,11 +117,11 @@ fn get_sgs_courses_assignment(
         }
         if !relevant_groups_for_course.is_empty() {
             // only this subset specialization groups consist course_id
-            optional_sgs_for_course.insert(course_id.clone(), relevant_groups_for_course);
+            optional_sgs_for_course.push((course_id.clone(), relevant_groups_for_course));
         }
     }
 
-    let mut courses_assignment = HashMap::new();
+    let mut courses_assignment = BTreeMap::new();
     find_valid_assignment_for_courses(
         sgs,
         groups_indices,
@@ -140,8 +139,8 @@ fn generate_sgs_subsets(
     sg_index: usize,
     groups_indices: &mut Vec<usize>,
     courses: &[CourseId],
-    best_match: &mut HashMap<CourseId, usize>,
-) -> Option<HashMap<CourseId, usize>> {
+    best_match: &mut BTreeMap<CourseId, usize>,
+) -> Option<BTreeMap<CourseId, usize>> {
     if groups_indices.len() == required_number_of_groups {
         return get_sgs_courses_assignment(sgs, groups_indices, courses, best_match);
     }
@@ -178,8 +177,8 @@ fn generate_sgs_subsets(
 fn run_exhaustive_search(
     sgs: &SpecializationGroups,
     courses: Vec<CourseId>, // list of all courses the user completed in specialization groups bank
-) -> HashMap<CourseId, usize> {
-    let mut best_match = HashMap::new();
+) -> BTreeMap<CourseId, usize> {
+    let mut best_match = BTreeMap::new();
     generate_sgs_subsets(
         &sgs.groups_list,
         sgs.groups_number,
@@ -213,20 +212,21 @@ impl<'a> BankRuleHandler<'a> {
         let credit_info = self.iterate_course_list();
         let mut completed_courses = Vec::new();
 
-        let find_replacement =
-            |replacements: &HashMap<String, Vec<String>>, course_id: &CourseId| -> Option<String> {
-                replacements
-                    .iter()
-                    .find_map(|(course_id_in_sg, course_id_in_sg_replacements)| {
-                        course_id_in_sg_replacements
-                            .contains(course_id)
-                            .then(|| course_id_in_sg.clone())
-                    })
-            };
+        let find_replacement = |replacements: &BTreeMap<String, Vec<String>>,
+                                course_id: &CourseId|
+         -> Option<String> {
+            replacements
+                .iter()
+                .find_map(|(course_id_in_sg, course_id_in_sg_replacements)| {
+                    course_id_in_sg_replacements
+                        .contains(course_id)
+                        .then(|| course_id_in_sg.clone())
+                })
+        };
 
         // hotfix for cases where the user has a course which is a replacement for a course in the specialization group bank and in another bank.
         // https://github.com/sogrim/technion-sogrim/issues/214#issuecomment-1478566102
-        let mut handled_courses = HashMap::new();
+        let mut handled_courses = BTreeMap::new();
         credit_info.handled_courses.into_iter().for_each(
             |(course_id_in_list, course_id_done_by_user)| {
                 if course_id_in_list != course_id_done_by_user {
@@ -260,8 +260,8 @@ impl<'a> BankRuleHandler<'a> {
         let valid_assignment_for_courses = run_exhaustive_search(sgs, completed_courses);
         let complete_sgs_indices =
             get_complete_sgs_indices(&sgs.groups_list, &valid_assignment_for_courses);
-        // The set is to prevent duplications
-        let mut sgs_names = HashSet::new();
+        // The set is to prevent duplications, the groups are ordered as in the catalog
+        let mut completed_sgs_indices = BTreeSet::new();
         valid_assignment_for_courses
             .into_iter()
             .for_each(|(course_id, sg_index)| {
@@ -278,13 +278,13 @@ impl<'a> BankRuleHandler<'a> {
                     if complete_sgs_indices.contains(&sg_index) {
                         course_status
                             .set_specialization_group_name(&sgs.groups_list[sg_index].name);
-                        sgs_names.insert(&sgs.groups_list[sg_index].name);
+                        completed_sgs_indices.insert(sg_index);
                     }
                 }
             });
 
-        sgs_names.into_iter().for_each(|sg_name| {
-            completed_groups.push(sg_name.clone());
+        completed_sgs_indices.into_iter().for_each(|sg_index| {
+            completed_groups.push(sgs.groups_list[sg_index].name.clone());
         });
 
         credit_info.sum_credit
@@ -446,6 +446,7 @@ async fn test_specialization_group() {
         course_bank_requirements: Vec::<Requirement>::new(),
         overflow_msgs: Vec::<String>::new(),
         total_credit: 0.0,
+        ..Default::default()
     };
     let course_list = vec![
         "236334".to_string(),
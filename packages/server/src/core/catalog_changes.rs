@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    resources::{catalog::Catalog, course::CourseId},
+};
+
+use super::types::Rule;
+
+// The edits which adapt a catalog to a new academic year, written by the owners as a small TOML file
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CatalogChanges {
+    pub name: String,              // The name of the new catalog
+    pub start_year: Option<usize>, // Defaults to the year after the start year of the cloned catalog
+    pub description: Option<String>,
+    pub total_credit: Option<f64>,
+    pub renumbered_courses: BTreeMap<CourseId, CourseId>, // Old course id to new course id, everywhere in the catalog
+    pub moved_courses: BTreeMap<CourseId, String>, // Course id to the bank it moves to, or is added to if it is not part of the catalog
+    pub removed_courses: Vec<CourseId>,
+    pub bank_credits: BTreeMap<String, f32>, // Bank name to its new credit requirement
+}
+
+impl CatalogChanges {
+    pub fn from_toml(toml: &str) -> Result<Self, AppError> {
+        toml::from_str(toml).map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+
+    fn check(&self, catalog: &Catalog) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+        if self.name.is_empty() {
+            errors.push("The new catalog must have a name".to_string());
+        }
+        for course_id in self.renumbered_courses.keys() {
+            if !catalog.course_to_bank.contains_key(course_id) {
+                errors.push(format!("Course {course_id} is not part of the catalog"));
+            }
+        }
+        for course_id in &self.removed_courses {
+            let renumbered = self
+                .renumbered_courses
+                .values()
+                .any(|new_id| new_id == course_id);
+            if !renumbered && !catalog.course_to_bank.contains_key(course_id) {
+                errors.push(format!("Course {course_id} is not part of the catalog"));
+            }
+        }
+        for bank_name in self.moved_courses.values().chain(self.bank_credits.keys()) {
+            if catalog.get_course_bank_by_name(bank_name).is_none() {
+                errors.push(format!("Bank {bank_name} is not part of the catalog"));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::BadRequest(errors.join("\n")))
+        }
+    }
+
+    // Returns a copy of the catalog with a new id and the changes applied.
+    // Renumberings are applied first, so moved and removed courses may be given by either id.
+    pub fn apply(&self, catalog: &Catalog) -> Result<Catalog, AppError> {
+        self.check(catalog)?;
+        let mut catalog = Catalog {
+            id: bson::oid::ObjectId::new(),
+            name: self.name.clone(),
+            start_year: self
+                .start_year
+                .or_else(|| catalog.start_year.map(|year| year + 1)),
+            ..catalog.clone()
+        };
+        if let Some(description) = &self.description {
+            catalog.description = description.clone();
+        }
+        if let Some(total_credit) = self.total_credit {
+            catalog.total_credit = total_credit;
+        }
+
+        for (old_id, new_id) in &self.renumbered_courses {
+            renumber_course(&mut catalog, old_id, new_id);
+        }
+        let renumbered = |course_id: &CourseId| {
+            self.renumbered_courses
+                .get(course_id)
+                .unwrap_or(course_id)
+                .clone()
+        };
+        for (course_id, bank_name) in &self.moved_courses {
+            catalog
+                .course_to_bank
+                .insert(renumbered(course_id), bank_name.clone());
+        }
+        for course_id in &self.removed_courses {
+            catalog.course_to_bank.remove(&renumbered(course_id));
+        }
+        for bank in catalog.course_banks.iter_mut() {
+            if let Some(credit) = self.bank_credits.get(&bank.name) {
+                bank.credit = Some(*credit);
+            }
+        }
+        Ok(catalog)
+    }
+}
+
+fn renumber_course(catalog: &mut Catalog, old_id: &CourseId, new_id: &CourseId) {
+    let renumber = |course_id: &mut CourseId| {
+        if course_id == old_id {
+            *course_id = new_id.clone();
+        }
+    };
+
+    if let Some(bank_name) = catalog.course_to_bank.remove(old_id) {
+        catalog.course_to_bank.insert(new_id.clone(), bank_name);
+    }
+    for replacements in [
+        &mut catalog.catalog_replacements,
+        &mut catalog.common_replacements,
+    ] {
+        if let Some(course_replacements) = replacements.remove(old_id) {
+            replacements.insert(new_id.clone(), course_replacements);
+        }
+        replacements.values_mut().flatten().for_each(renumber);
+    }
+    for bank in catalog.course_banks.iter_mut() {
+        match &mut bank.rule {
+            Rule::Chains(chains) => chains.iter_mut().flatten().for_each(renumber),
+            Rule::SpecializationGroups(specialization_groups) => {
+                for group in specialization_groups.groups_list.iter_mut() {
+                    group.course_list.iter_mut().for_each(renumber);
+                    group
+                        .mandatory
+                        .iter_mut()
+                        .flatten()
+                        .flatten()
+                        .for_each(renumber);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(english_requirement) = catalog.english_requirement.as_mut() {
+        renumber(&mut english_requirement.level_course);
+    }
+    catalog
+        .medicine_policy
+        .clinical_rotations
+        .iter_mut()
+        .for_each(renumber);
+}
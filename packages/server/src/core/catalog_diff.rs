@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{catalog::Catalog, course::CourseId};
+
+use super::types::Rule;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BankChange {
+    pub bank_name: String,
+    pub old_rule: Rule,
+    pub new_rule: Rule,
+    pub old_credit: Option<f32>,
+    pub new_credit: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CourseMove {
+    pub course_id: CourseId,
+    pub from_bank: Option<String>, // None if the course was added to the catalog
+    pub to_bank: Option<String>,   // None if the course was removed from the catalog
+}
+
+// The structural changes between two catalogs, usually two versions of the same catalog
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CatalogDiff {
+    pub added_banks: Vec<String>,
+    pub removed_banks: Vec<String>,
+    pub changed_banks: Vec<BankChange>, // Banks whose rule or credit requirement changed
+    pub moved_courses: Vec<CourseMove>,
+}
+
+pub fn diff(old: &Catalog, new: &Catalog) -> CatalogDiff {
+    let added_banks = new
+        .course_banks
+        .iter()
+        .filter(|bank| old.get_course_bank_by_name(&bank.name).is_none())
+        .map(|bank| bank.name.clone())
+        .collect();
+    let removed_banks = old
+        .course_banks
+        .iter()
+        .filter(|bank| new.get_course_bank_by_name(&bank.name).is_none())
+        .map(|bank| bank.name.clone())
+        .collect();
+    let changed_banks = old
+        .course_banks
+        .iter()
+        .filter_map(|old_bank| {
+            let new_bank = new.get_course_bank_by_name(&old_bank.name)?;
+            (old_bank.rule != new_bank.rule || old_bank.credit != new_bank.credit).then(|| {
+                BankChange {
+                    bank_name: old_bank.name.clone(),
+                    old_rule: old_bank.rule.clone(),
+                    new_rule: new_bank.rule.clone(),
+                    old_credit: old_bank.credit,
+                    new_credit: new_bank.credit,
+                }
+            })
+        })
+        .collect();
+
+    // The course maps are ordered, so the moves are ordered by course id
+    let mut course_ids = old
+        .course_to_bank
+        .keys()
+        .chain(new.course_to_bank.keys())
+        .collect::<Vec<_>>();
+    course_ids.sort();
+    course_ids.dedup();
+    let moved_courses = course_ids
+        .into_iter()
+        .filter_map(|course_id| {
+            let from_bank = old.course_to_bank.get(course_id);
+            let to_bank = new.course_to_bank.get(course_id);
+            (from_bank != to_bank).then(|| CourseMove {
+                course_id: course_id.clone(),
+                from_bank: from_bank.cloned(),
+                to_bank: to_bank.cloned(),
+            })
+        })
+        .collect();
+
+    CatalogDiff {
+        added_banks,
+        removed_banks,
+        changed_banks,
+        moved_courses,
+    }
+}
@@ -1,8 +1,246 @@
-use crate::{error::AppError, resources::catalog::Catalog};
+use std::collections::HashSet;
 
-use super::credit_transfer_graph::validate_acyclic_credit_transfer_graph;
+use serde::{Deserialize, Serialize};
 
+use crate::{
+    error::AppError,
+    resources::{
+        catalog::Catalog,
+        course::{Course, CourseId},
+    },
+};
+
+use super::{credit_transfer_graph::validate_acyclic_credit_transfer_graph, messages, types::Rule};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,   // The catalog can't be used for computing degree statuses
+    Warning, // The catalog can be used, but it is probably not what its author meant
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CatalogFinding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogReport {
+    pub findings: Vec<CatalogFinding>,
+}
+
+impl CatalogReport {
+    fn error(&mut self, message: String) {
+        self.findings.push(CatalogFinding {
+            severity: Severity::Error,
+            message,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.findings.push(CatalogFinding {
+            severity: Severity::Warning,
+            message,
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &CatalogFinding> {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    // Fails with all the errors of the report, warnings don't fail the validation
+    pub fn into_result(self) -> Result<Self, AppError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(AppError::BadRequest(
+                self.errors()
+                    .map(|finding| finding.message.clone())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ))
+        }
+    }
+}
+
+fn check_banks(catalog: &Catalog, report: &mut CatalogReport) {
+    for (course_id, bank_name) in &catalog.course_to_bank {
+        if catalog.get_course_bank_by_name(bank_name).is_none() {
+            report.error(messages::unknown_bank_for_course(course_id, bank_name));
+        }
+    }
+
+    let mut malformed_overflows = false;
+    let mut overflow_pairs = HashSet::new();
+    for credit_overflow in &catalog.credit_overflows {
+        // Only one of the transfers between the same banks would be applied
+        if !overflow_pairs.insert((&credit_overflow.from, &credit_overflow.to)) {
+            malformed_overflows = true;
+            report.error(messages::duplicate_credit_overflow(
+                &credit_overflow.from,
+                &credit_overflow.to,
+            ));
+        }
+        for bank_name in [&credit_overflow.from, &credit_overflow.to] {
+            if catalog.get_course_bank_by_name(bank_name).is_none() {
+                malformed_overflows = true;
+                report.error(messages::unknown_bank_for_credit_overflow(
+                    &credit_overflow.from,
+                    &credit_overflow.to,
+                    bank_name,
+                ));
+            }
+        }
+        // A ratio which isn't positive would take credit from the target bank or divide by zero
+        if let Some(ratio) = credit_overflow.ratio.filter(|ratio| *ratio <= 0.0) {
+            report.error(messages::invalid_credit_overflow_ratio(
+                &credit_overflow.from,
+                &credit_overflow.to,
+                ratio,
+            ));
+        }
+        if let Some(cap) = credit_overflow.cap.filter(|cap| *cap < 0.0) {
+            report.error(messages::invalid_credit_overflow_cap(
+                &credit_overflow.from,
+                &credit_overflow.to,
+                cap,
+            ));
+        }
+    }
+    // The graph can't be built when the transfers refer to banks which don't exist or repeat themselves, which is reported above
+    if !malformed_overflows {
+        if let Err(e) = validate_acyclic_credit_transfer_graph(catalog) {
+            report.error(e.to_string());
+        }
+    }
+}
+
+fn check_bank_rules(catalog: &Catalog, report: &mut CatalogReport) {
+    for bank in &catalog.course_banks {
+        let course_list = catalog.get_course_list(&bank.name);
+        let check_in_bank = |course_id: &CourseId, report: &mut CatalogReport| {
+            if !course_list.contains(course_id) {
+                report.error(messages::course_not_in_bank(course_id, &bank.name));
+            }
+        };
+        match &bank.rule {
+            Rule::Chains(chains) => chains
+                .iter()
+                .flatten()
+                .for_each(|course_id| check_in_bank(course_id, report)),
+            Rule::SpecializationGroups(specialization_groups) => {
+                let groups_count = specialization_groups.groups_list.len();
+                if specialization_groups.groups_number > groups_count {
+                    report.error(messages::too_many_required_specialization_groups(
+                        &bank.name,
+                        specialization_groups.groups_number,
+                        groups_count,
+                    ));
+                }
+                for group in &specialization_groups.groups_list {
+                    group
+                        .course_list
+                        .iter()
+                        .for_each(|course_id| check_in_bank(course_id, report));
+                    group
+                        .mandatory
+                        .iter()
+                        .flatten()
+                        .flatten()
+                        .filter(|course_id| !group.course_list.contains(course_id))
+                        .for_each(|course_id| {
+                            report.error(messages::mandatory_course_not_in_specialization_group(
+                                course_id,
+                                &group.name,
+                            ))
+                        });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_total_credit(catalog: &Catalog, report: &mut CatalogReport) {
+    // Credit is given in halves, so a larger difference isn't a rounding error of the sum
+    const CREDIT_TOLERANCE: f64 = 0.01;
+    // The credit of banks without a credit requirement is unknown, so the total can't be compared
+    let Some(banks_credit) = catalog
+        .course_banks
+        .iter()
+        .map(|bank| bank.credit)
+        .sum::<Option<f32>>()
+    else {
+        return;
+    };
+    if (banks_credit as f64 - catalog.total_credit).abs() > CREDIT_TOLERANCE {
+        report.warning(messages::banks_credit_mismatch(
+            banks_credit,
+            catalog.total_credit as f32,
+        ));
+    }
+}
+
+fn check_courses(catalog: &Catalog, courses: &[Course], report: &mut CatalogReport) {
+    let course_ids = courses
+        .iter()
+        .map(|course| &course.id)
+        .collect::<HashSet<_>>();
+    for course_id in catalog.course_to_bank.keys() {
+        if !course_ids.contains(course_id) {
+            report.warning(messages::unknown_course_in_catalog(course_id));
+        }
+    }
+    for (course_id, replacements) in catalog
+        .catalog_replacements
+        .iter()
+        .chain(&catalog.common_replacements)
+    {
+        for replacement in replacements {
+            if !course_ids.contains(replacement) {
+                report.warning(messages::unknown_course_in_replacement(
+                    course_id,
+                    replacement,
+                ));
+            }
+        }
+        if !course_ids.contains(course_id) {
+            report.warning(messages::unknown_course_in_catalog(course_id));
+        }
+    }
+}
+
+// Returns all the problems which were found in the catalog.
+// The referenced courses are checked only when the courses are given.
+pub fn catalog_report(catalog: &Catalog, courses: Option<&[Course]>) -> CatalogReport {
+    let mut report = CatalogReport::default();
+    check_banks(catalog, &mut report);
+    check_bank_rules(catalog, &mut report);
+    check_total_credit(catalog, &mut report);
+    if let Some(courses) = courses {
+        check_courses(catalog, courses, &mut report);
+    }
+    report
+}
+
+// Fails with all the errors of the catalog, without checking its courses
+#[cfg(test)]
 pub fn validate_catalog(catalog: &Catalog) -> Result<(), AppError> {
-    validate_acyclic_credit_transfer_graph(catalog)?;
-    Ok(())
+    catalog_report(catalog, None).into_result().map(|_| ())
+}
+
+// Fails only when degree statuses can't be computed against the catalog, i.e its course banks can't be ordered
+// due to an unknown bank or a cycle in the credit transfers. Students may keep choosing catalogs with other findings,
+// which are reported to the owners.
+pub fn validate_computable_catalog(catalog: &Catalog) -> Result<(), AppError> {
+    catalog.get_bank_traversal_order().map(|_| ()).map_err(|e| {
+        AppError::BadRequest(messages::corrupted_catalog(&catalog.name, &e.to_string()))
+    })
 }
@@ -1,66 +1,250 @@
+use std::collections::HashMap;
+
 use petgraph::algo::toposort;
+use petgraph::dot::{Config, Dot};
 use petgraph::Graph;
+use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
 use crate::resources::catalog::Catalog;
 use crate::resources::course::CourseBank;
 
+use super::degree_status::DegreeStatus;
 use super::messages;
-use super::types::CreditOverflow;
+use super::types::{CreditOverflow, Rule, TransferKind};
+
+// The reasons for which the course banks of a catalog can't be ordered by their credit transfers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CreditTransferError {
+    UnknownBank,   // A credit overflow refers to a bank which doesn't exist
+    Cycle(String), // The credit overflows have a cycle which starts and ends in the bank
+}
+
+impl std::fmt::Display for CreditTransferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CreditTransferError::UnknownBank => {
+                write!(f, "{}", messages::build_credit_transfer_graph_failed())
+            }
+            CreditTransferError::Cycle(bank_name) => {
+                write!(f, "{}", messages::cyclic_credit_transfer_graph(bank_name))
+            }
+        }
+    }
+}
+
+impl From<CreditTransferError> for AppError {
+    fn from(err: CreditTransferError) -> Self {
+        AppError::BadRequest(err.to_string())
+    }
+}
 
 fn build_credit_transfer_graph(
     course_banks: &[CourseBank],
     credit_overflow_rules: &[CreditOverflow],
-) -> Result<Graph<String, ()>, AppError> {
+) -> Result<Graph<String, ()>, CreditTransferError> {
     let mut g = Graph::<String, ()>::new();
     for course_bank in course_banks {
         g.add_node(course_bank.name.clone());
     }
+    let mut add_edge = |from: &str, to: &str| match (
+        g.node_indices().find(|i| g[*i] == from),
+        g.node_indices().find(|i| g[*i] == to),
+    ) {
+        (Some(from), Some(to)) => {
+            g.add_edge(from, to, ());
+            Ok(())
+        }
+        _ => Err(CreditTransferError::UnknownBank),
+    };
     for credit_rule in credit_overflow_rules {
-        match (
-            g.node_indices().find(|i| g[*i] == credit_rule.from),
-            g.node_indices().find(|i| g[*i] == credit_rule.to),
-        ) {
-            (Some(from), Some(to)) => g.add_edge(from, to, ()),
-            _ => {
-                return Err(AppError::BadRequest(
-                    messages::build_credit_transfer_graph_failed(),
-                ))
-            }
-        };
+        add_edge(&credit_rule.from, &credit_rule.to)?;
+    }
+    // A bank with several targets for its excess credit fills them by their priority,
+    // so each target must be traversed before the target which comes after it.
+    for (i, credit_rule) in credit_overflow_rules.iter().enumerate() {
+        if let Some(next_credit_rule) = credit_overflow_rules[i + 1..]
+            .iter()
+            .find(|next_credit_rule| next_credit_rule.from == credit_rule.from)
+        {
+            add_edge(&credit_rule.to, &next_credit_rule.to)?;
+        }
     }
     Ok(g)
 }
 
-pub fn find_traversal_order(catalog: &Catalog) -> Vec<CourseBank> {
-    let g = match build_credit_transfer_graph(&catalog.course_banks, &catalog.credit_overflows) {
-        Ok(graph) => graph,
-        Err(_) => {
-            log::error!("corrupted catalog in the database - return empty list");
-            return vec![];
-        }
-    };
-    let order = toposort(&g, None).unwrap_or_else(|_| {
-        log::error!(
-            "corrupted catalog in the database - course banks will be set in an arbitrary order"
-        );
-        g.node_indices().collect::<Vec<_>>()
-    });
+pub fn find_traversal_order(catalog: &Catalog) -> Result<Vec<CourseBank>, CreditTransferError> {
+    let g = build_credit_transfer_graph(&catalog.course_banks, &catalog.credit_overflows)?;
+    let order =
+        toposort(&g, None).map_err(|e| CreditTransferError::Cycle(g[e.node_id()].clone()))?;
     let mut ordered_course_banks = Vec::<CourseBank>::new();
     for node in order {
         if let Some(bank) = catalog.get_course_bank_by_name(&g[node]) {
             ordered_course_banks.push(bank.clone());
         }
     }
-    ordered_course_banks
+    Ok(ordered_course_banks)
 }
 
 pub fn validate_acyclic_credit_transfer_graph(catalog: &Catalog) -> Result<(), AppError> {
-    let g = build_credit_transfer_graph(&catalog.course_banks, &catalog.credit_overflows)?;
-    match toposort(&g, None) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(AppError::BadRequest(
-            messages::cyclic_credit_transfer_graph(&g[e.node_id()]),
-        )),
+    find_traversal_order(catalog)?;
+    Ok(())
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GraphNode {
+    pub name: String,
+    pub rule: Rule,
+    pub credit: Option<f32>,
+}
+
+// What was actually transferred along a credit overflow in a student's degree status
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Transfers {
+    pub credit: f32,
+    pub missing_credit: f32, // Credit requirement which was moved to the target because the source bank has fewer credit than required
+    pub courses: f32,
+    pub discarded: f32, // Credit which exceeded the cap of the overflow
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub cap: Option<f32>,
+    pub ratio: Option<f32>,
+    pub priority: usize, // The order of the target among the targets of the source bank's excess credit, starting from 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfers: Option<Transfers>,
+}
+
+// The course banks of a catalog and the credit overflows between them, for debugging overflow chains
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CreditTransferGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl CreditTransferGraph {
+    pub fn new(catalog: &Catalog) -> Result<Self, AppError> {
+        // Fails if an overflow refers to a bank which doesn't exist
+        build_credit_transfer_graph(&catalog.course_banks, &catalog.credit_overflows)?;
+        let nodes = catalog
+            .course_banks
+            .iter()
+            .map(|bank| GraphNode {
+                name: bank.name.clone(),
+                rule: bank.rule.clone(),
+                credit: bank.credit,
+            })
+            .collect();
+        let edges = catalog
+            .credit_overflows
+            .iter()
+            .enumerate()
+            .map(|(i, credit_rule)| GraphEdge {
+                from: credit_rule.from.clone(),
+                to: credit_rule.to.clone(),
+                cap: credit_rule.cap,
+                ratio: credit_rule.ratio,
+                priority: catalog.credit_overflows[..i]
+                    .iter()
+                    .filter(|prev_credit_rule| prev_credit_rule.from == credit_rule.from)
+                    .count(),
+                transfers: None,
+            })
+            .collect();
+        Ok(CreditTransferGraph { nodes, edges })
+    }
+
+    // Annotates the edges with what was transferred along them, according to the transfers of the degree status
+    pub fn annotate(&mut self, degree_status: &DegreeStatus) {
+        self.edges
+            .iter_mut()
+            .for_each(|edge| edge.transfers = Some(Transfers::default()));
+        for transfer in &degree_status.transfers {
+            let Some(edge_transfers) = self
+                .edges
+                .iter_mut()
+                .find(|edge| edge.from == transfer.from && edge.to == transfer.to)
+                .and_then(|edge| edge.transfers.as_mut())
+            else {
+                continue;
+            };
+            let amount = match transfer.kind {
+                TransferKind::Credit => &mut edge_transfers.credit,
+                TransferKind::MissingCredit => &mut edge_transfers.missing_credit,
+                TransferKind::Courses => &mut edge_transfers.courses,
+                TransferKind::DiscardedCredit => &mut edge_transfers.discarded,
+            };
+            *amount += transfer.amount;
+        }
+    }
+
+    // Renders the graph in the Graphviz DOT language
+    pub fn to_dot(&self) -> String {
+        let mut g = Graph::<&GraphNode, &GraphEdge>::new();
+        let node_indices = self
+            .nodes
+            .iter()
+            .map(|node| (node.name.as_str(), g.add_node(node)))
+            .collect::<HashMap<_, _>>();
+        for edge in &self.edges {
+            if let (Some(from), Some(to)) = (
+                node_indices.get(edge.from.as_str()),
+                node_indices.get(edge.to.as_str()),
+            ) {
+                g.add_edge(*from, *to, edge);
+            }
+        }
+        format!(
+            "{:?}",
+            Dot::with_attr_getters(
+                &g,
+                &[Config::NodeNoLabel, Config::EdgeNoLabel],
+                &|_, edge| format!("label = \"{}\"", edge_label(edge.weight())),
+                &|_, (_, node)| format!("label = \"{}\"", node_label(node)),
+            )
+        )
+    }
+}
+
+// Escapes text for a quoted DOT string, the backslashes first so the escaped quotes stay escaped
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// The lines of the labels are joined with DOT's "\n" escape, so only their text is escaped
+fn node_label(node: &GraphNode) -> String {
+    let (name, rule) = (escape(&node.name), escape(&node.rule.to_string()));
+    match node.credit {
+        Some(credit) => format!("{name}\\n{rule} ({credit})"),
+        None => format!("{name}\\n{rule}"),
+    }
+}
+
+fn edge_label(edge: &GraphEdge) -> String {
+    let mut lines = Vec::new();
+    if edge.priority > 0 {
+        lines.push(format!("priority {}", edge.priority));
+    }
+    if let Some(cap) = edge.cap {
+        lines.push(format!("cap {cap}"));
+    }
+    if let Some(ratio) = edge.ratio {
+        lines.push(format!("ratio {ratio}"));
+    }
+    if let Some(transfers) = &edge.transfers {
+        for (name, value) in [
+            ("transferred", transfers.credit),
+            ("missing credit", transfers.missing_credit),
+            ("courses", transfers.courses),
+            ("discarded", transfers.discarded),
+        ] {
+            if value > 0.0 {
+                lines.push(format!("{name} {value}"));
+            }
+        }
     }
+    lines.join("\\n")
 }
@@ -0,0 +1,77 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    catalog::Catalog,
+    course::{Course, CourseId},
+};
+
+use super::{CorruptedCatalog, DegreeStatus};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogStatus {
+    pub catalog_id: bson::oid::ObjectId,
+    pub catalog_name: String,
+    pub degree_status: DegreeStatus,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogComparison {
+    pub statuses: Vec<CatalogStatus>,
+    pub closest_catalog: Option<bson::oid::ObjectId>, // The catalog which gets the user closest to graduation
+}
+
+impl DegreeStatus {
+    // Orders degree statuses by how close they get the user to graduation
+    fn closer_to_graduation(&self, other: &DegreeStatus) -> Ordering {
+        other
+            .summary
+            .completed
+            .cmp(&self.summary.completed)
+            .then(
+                self.summary
+                    .remaining_credit
+                    .total_cmp(&other.summary.remaining_credit),
+            )
+            .then(
+                self.summary
+                    .incomplete_banks
+                    .len()
+                    .cmp(&other.summary.incomplete_banks.len()),
+            )
+    }
+
+    // Computes the status of the user against each of the catalogs without changing the user's own status
+    pub fn compare_catalogs(
+        &self,
+        catalogs: Vec<Catalog>,
+        courses: HashMap<CourseId, Course>,
+    ) -> Result<CatalogComparison, CorruptedCatalog> {
+        let statuses = catalogs
+            .into_iter()
+            .map(|catalog| {
+                let mut degree_status = self.clone();
+                degree_status.clear_types();
+                let catalog_id = catalog.id;
+                let catalog_name = catalog.name.clone();
+                degree_status.compute(catalog, courses.clone())?;
+                Ok(CatalogStatus {
+                    catalog_id,
+                    catalog_name,
+                    degree_status,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let closest_catalog = statuses
+            .iter()
+            .min_by(|a, b| a.degree_status.closer_to_graduation(&b.degree_status))
+            .map(|catalog_status| catalog_status.catalog_id);
+
+        Ok(CatalogComparison {
+            statuses,
+            closest_catalog,
+        })
+    }
+}
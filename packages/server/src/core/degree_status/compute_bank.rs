@@ -1,12 +1,33 @@
 use crate::core::types::Requirement;
 use crate::{
     core::{bank_rule::BankRuleHandler, messages, types::Rule},
-    resources::course::{CourseBank, CourseId},
+    resources::{
+        catalog::ExemptionPolicy,
+        course::{CourseBank, CourseId, Grade},
+    },
 };
 
 use super::DegreeStatusHandler;
 
 impl<'a> DegreeStatusHandler<'a> {
+    // Returns the credit of the courses in the bank which the student got an exemption without credit for.
+    // The grade sheet doesn't include the credit of such courses, so it is taken from the courses collection.
+    fn exempted_credit(&self, bank_name: &str) -> f32 {
+        self.degree_status
+            .course_statuses
+            .iter()
+            .filter(|course_status| {
+                course_status.r#type.as_deref() == Some(bank_name)
+                    && course_status.grade == Some(Grade::ExemptionWithoutCredit)
+            })
+            .filter_map(|course_status| {
+                self.courses
+                    .get(&course_status.course.id)
+                    .map(|course| (course.credit - course_status.course.credit).max(0.0))
+            })
+            .sum()
+    }
+
     pub fn compute_bank(
         &mut self,
         bank: CourseBank,
@@ -46,9 +67,28 @@ impl<'a> DegreeStatusHandler<'a> {
                         missing_credit = credit - sum_credit_requirement;
                     }
                 }
-                if missing_credit > 0.0 {
+                let exempted_credit = self.exempted_credit(&bank.name).min(missing_credit);
+                let transferred_credit = match self.catalog.exemption_policy {
+                    ExemptionPolicy::TransferCredit => {
+                        if exempted_credit > 0.0 {
+                            self.exempted_credit_map
+                                .insert(bank.name.clone(), exempted_credit);
+                        }
+                        missing_credit
+                    }
+                    ExemptionPolicy::ReduceCredit => {
+                        if exempted_credit > 0.0 {
+                            self.degree_status.summary.reduced_credit += exempted_credit;
+                            self.degree_status.add_overflow_msg(
+                                messages::exemption_reduced_credit_msg(exempted_credit, &bank.name),
+                            );
+                        }
+                        missing_credit - exempted_credit
+                    }
+                };
+                if transferred_credit > 0.0 {
                     self.missing_credit_map
-                        .insert(bank.name.clone(), missing_credit);
+                        .insert(bank.name.clone(), transferred_credit);
                 }
             }
             Rule::AccumulateCredit => sum_credit = bank_rule_handler.accumulate_credit(),
@@ -95,11 +135,19 @@ impl<'a> DegreeStatusHandler<'a> {
         match bank.credit {
             Some(bank_credit) => {
                 let new_bank_credit = bank_credit - missing_credit + missing_credit_from_prev_banks;
+                sum_credit += self.calculate_prioritized_credit_overflows(
+                    &bank.name,
+                    new_bank_credit - sum_credit,
+                );
                 sum_credit = self.handle_credit_overflow(&bank, new_bank_credit, sum_credit);
                 completed &= sum_credit >= new_bank_credit;
                 requirement.credit_requirement(new_bank_credit);
             }
-            None => sum_credit = self.handle_credit_overflow(&bank, 0.0, sum_credit),
+            None => {
+                sum_credit +=
+                    self.calculate_prioritized_credit_overflows(&bank.name, f32::INFINITY);
+                sum_credit = self.handle_credit_overflow(&bank, 0.0, sum_credit);
+            }
         };
 
         requirement
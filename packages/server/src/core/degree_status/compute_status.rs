@@ -13,11 +13,9 @@ impl<'a> DegreeStatusHandler<'a> {
 
             if bank.credit.is_none() {
                 // Add a message where this bank's credit are counted.
-                if let Some(to_bank_name) = self.find_next_bank_with_credit_requirement(&bank.name)
-                {
+                for to_bank_name in self.find_next_banks_with_credit_requirement(&bank.name) {
                     self.degree_status
-                        .overflow_msgs
-                        .push(messages::credit_overflow_detailed_msg(
+                        .add_overflow_msg(messages::credit_overflow_detailed_msg(
                             &bank.name,
                             &to_bank_name,
                         ));
@@ -36,7 +34,6 @@ impl<'a> DegreeStatusHandler<'a> {
         let credit_leftovers = self.credit_overflow_map.values().sum(); // if different from 0 then the user has extra credit he doesn't use
         self.degree_status.total_credit += credit_leftovers;
         self.degree_status
-            .overflow_msgs
-            .push(messages::credit_leftovers_msg(credit_leftovers));
+            .add_overflow_msg(messages::credit_leftovers_msg(credit_leftovers));
     }
 }
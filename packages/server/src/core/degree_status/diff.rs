@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::Requirement;
+use crate::resources::course::CourseId;
+
+use super::DegreeStatus;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RequirementChange {
+    pub course_bank_name: String,
+    pub before: Option<Requirement>, // None if the bank didn't exist in the older status
+    pub after: Option<Requirement>,  // None if the bank doesn't exist in the newer status
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CourseAssignmentChange {
+    pub course_id: CourseId,
+    pub semester: Option<String>,
+    pub before: Option<String>, // The bank the course was assigned to in the older status
+    pub after: Option<String>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct DegreeStatusDiff {
+    pub requirements: Vec<RequirementChange>,
+    pub course_assignments: Vec<CourseAssignmentChange>,
+    pub added_msgs: Vec<String>,
+    pub removed_msgs: Vec<String>,
+}
+
+impl DegreeStatus {
+    fn requirement(&self, bank_name: &str) -> Option<&Requirement> {
+        self.course_bank_requirements
+            .iter()
+            .find(|requirement| requirement.course_bank_name == bank_name)
+    }
+
+    fn course_assignment(&self, id: &str, semester: &Option<String>) -> Option<String> {
+        self.course_statuses
+            .iter()
+            .find(|course_status| {
+                course_status.course.id == id && course_status.semester == *semester
+            })
+            .and_then(|course_status| course_status.r#type.clone())
+    }
+
+    // Returns the changes between this (older) status and the given (newer) status
+    pub fn diff(&self, other: &DegreeStatus) -> DegreeStatusDiff {
+        let mut diff = DegreeStatusDiff::default();
+
+        let bank_names = self
+            .course_bank_requirements
+            .iter()
+            .chain(other.course_bank_requirements.iter())
+            .map(|requirement| requirement.course_bank_name.as_str());
+        for bank_name in bank_names {
+            if diff
+                .requirements
+                .iter()
+                .any(|change| change.course_bank_name == bank_name)
+            {
+                continue;
+            }
+            let (before, after) = (self.requirement(bank_name), other.requirement(bank_name));
+            if before != after {
+                diff.requirements.push(RequirementChange {
+                    course_bank_name: bank_name.to_string(),
+                    before: before.cloned(),
+                    after: after.cloned(),
+                });
+            }
+        }
+
+        for course_status in self
+            .course_statuses
+            .iter()
+            .chain(other.course_statuses.iter())
+        {
+            let (id, semester) = (&course_status.course.id, &course_status.semester);
+            let (before, after) = (
+                self.course_assignment(id, semester),
+                other.course_assignment(id, semester),
+            );
+            let change = CourseAssignmentChange {
+                course_id: id.clone(),
+                semester: semester.clone(),
+                before,
+                after,
+            };
+            if change.before != change.after && !diff.course_assignments.contains(&change) {
+                diff.course_assignments.push(change);
+            }
+        }
+
+        diff.added_msgs = other
+            .overflow_msgs
+            .iter()
+            .filter(|msg| !self.overflow_msgs.contains(msg))
+            .cloned()
+            .collect();
+        diff.removed_msgs = self
+            .overflow_msgs
+            .iter()
+            .filter(|msg| !other.overflow_msgs.contains(msg))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
@@ -0,0 +1,40 @@
+use crate::core::locale::Language;
+
+use super::DegreeStatus;
+
+impl DegreeStatus {
+    // Renders the messages of the status in the given language.
+    // Statuses which were computed before messages were typed are left as is.
+    pub fn localize(&mut self, language: Language) {
+        if self.typed_overflow_msgs.len() == self.overflow_msgs.len() {
+            // Unmet conditions are also overflow messages
+            for condition in self.summary.unmet_conditions.iter_mut() {
+                if let Some(msg) = self.typed_overflow_msgs.iter().find(|msg| {
+                    Language::ALL
+                        .into_iter()
+                        .any(|language| msg.render(language) == *condition)
+                }) {
+                    *condition = msg.render(language);
+                }
+            }
+            self.overflow_msgs = self
+                .typed_overflow_msgs
+                .iter()
+                .map(|msg| msg.render(language))
+                .collect();
+        }
+        for requirement in self.course_bank_requirements.iter_mut() {
+            if let Some(msg) = &requirement.typed_message {
+                requirement.message = Some(msg.render(language));
+            }
+        }
+        for course_status in self.course_statuses.iter_mut() {
+            if let Some(msg) = &course_status.typed_additional_msg {
+                course_status.additional_msg = Some(msg.render(language));
+            }
+        }
+        for secondary_status in self.secondary_statuses.iter_mut() {
+            secondary_status.degree_status.localize(language);
+        }
+    }
+}
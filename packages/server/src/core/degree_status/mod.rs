@@ -1,24 +1,79 @@
+pub mod comparison;
 pub mod compute_bank;
 pub mod compute_status;
+pub mod diff;
+pub mod localization;
 pub mod overflow;
 pub mod postprocessing;
 pub mod preprocessing;
+pub mod secondary_catalogs;
+pub mod summary;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::core::types::Requirement;
+use crate::core::credit_transfer_graph::CreditTransferError;
+use crate::core::faculty_rules;
+use crate::core::messages;
+use crate::core::messages::Message;
+use crate::core::types::{
+    BankTransfer, CreditOverflow, DegreeSummary, Requirement, SharedCourse, TransferKind,
+};
+use crate::error::AppError;
 use crate::resources::{
     catalog::Catalog,
-    course::{Course, CourseBank, CourseId, CourseState, CourseStatus},
+    course::{Course, CourseBank, CourseId, CourseState, CourseStatus, Grade},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct DegreeStatus {
     pub course_statuses: Vec<CourseStatus>,
     pub course_bank_requirements: Vec<Requirement>,
     pub overflow_msgs: Vec<String>,
+    #[serde(default)]
+    pub typed_overflow_msgs: Vec<Message>, // The same messages as overflow_msgs, with their codes and parameters
+    #[serde(default)]
+    pub transfers: Vec<BankTransfer>, // The transfers between the banks, including the ones which have no message
     pub total_credit: f32,
+    #[serde(default)]
+    pub summary: DegreeSummary,
+    #[serde(default)]
+    pub secondary_statuses: Vec<SecondaryDegreeStatus>,
+    #[serde(default)]
+    pub shared_courses: Vec<SharedCourse>, // Courses which are counted (or were withheld) by both the primary and a secondary catalog
+}
+
+// The status of the user against a secondary catalog (double degree or minor)
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct SecondaryDegreeStatus {
+    pub catalog_id: bson::oid::ObjectId,
+    pub catalog_name: String,
+    pub degree_status: DegreeStatus,
+}
+
+// The degree status can't be computed against a catalog whose course banks can't be ordered
+#[derive(Clone, Debug)]
+pub struct CorruptedCatalog {
+    pub catalog_id: bson::oid::ObjectId,
+    pub catalog_name: String,
+    pub error: CreditTransferError,
+}
+
+impl std::fmt::Display for CorruptedCatalog {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            messages::corrupted_catalog(&self.catalog_name, &self.error.to_string())
+        )
+    }
+}
+
+impl From<CorruptedCatalog> for AppError {
+    fn from(err: CorruptedCatalog) -> Self {
+        AppError::InternalServer(err.to_string())
+    }
 }
 
 impl DegreeStatus {
@@ -52,7 +107,8 @@ impl DegreeStatus {
             .collect()
     }
 
-    // This function gets a list of courses and sets their state to "in progress"
+    // This function gets a list of courses and sets their state to "in progress",
+    // in this status and in the statuses against the secondary catalogs, which were computed from the same courses
     pub fn set_to_in_progress(&mut self, course_list: Vec<(CourseId, Option<String>)>) {
         self.course_statuses
             .iter_mut()
@@ -64,7 +120,75 @@ impl DegreeStatus {
             })
             .for_each(|course_status| {
                 course_status.state = Some(CourseState::InProgress);
+            });
+        self.secondary_statuses
+            .iter_mut()
+            .for_each(|secondary_status| {
+                secondary_status
+                    .degree_status
+                    .set_to_in_progress(course_list.clone())
+            });
+    }
+
+    pub fn add_overflow_msg(&mut self, msg: Message) {
+        self.overflow_msgs.push(msg.to_string());
+        self.typed_overflow_msgs.push(msg);
+    }
+
+    // Records a transfer along the credit overflow, whether or not it is reported by a message
+    pub fn add_transfer(&mut self, rule: &CreditOverflow, amount: f32, kind: TransferKind) {
+        if amount > 0.0 {
+            self.transfers.push(BankTransfer {
+                from: rule.from.clone(),
+                to: rule.to.clone(),
+                amount,
+                kind,
+            });
+        }
+    }
+
+    // Identifies the input of a computation, i.e the courses of the user and the catalog.
+    // The hash is stored with cached statuses, so it must be stable across builds of the server.
+    pub fn input_hash(&self, catalog_id: &bson::oid::ObjectId) -> String {
+        // Only the fields which the computation depends on
+        #[derive(Serialize)]
+        struct CourseInput<'a> {
+            id: &'a CourseId,
+            grade: &'a Option<Grade>,
+            state: &'a Option<CourseState>,
+            semester: &'a Option<String>,
+            r#type: &'a Option<String>,
+        }
+        let inputs = self
+            .course_statuses
+            .iter()
+            .map(|course_status| CourseInput {
+                id: &course_status.course.id,
+                grade: &course_status.grade,
+                state: &course_status.state,
+                semester: &course_status.semester,
+                r#type: &course_status.r#type,
             })
+            .collect::<Vec<_>>();
+        let mut hasher = Sha256::new();
+        hasher.update(catalog_id.to_hex());
+        hasher.update(serde_json::to_string(&inputs).unwrap_or_default());
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    // The course types are only meaningful in the context of the catalog they were computed against,
+    // so they are cleared whenever the status is computed against a different catalog
+    pub fn clear_types(&mut self) {
+        self.course_statuses.iter_mut().for_each(|course_status| {
+            course_status.r#type = None;
+            course_status.specialization_group_name = None;
+            course_status.additional_msg = None;
+            course_status.typed_additional_msg = None;
+        });
     }
 
     pub fn fill_tags(&mut self, courses: &[Course]) {
@@ -82,51 +206,71 @@ pub struct DegreeStatusHandler<'a> {
     course_banks: Vec<CourseBank>,
     catalog: &'a Catalog,
     courses: HashMap<CourseId, Course>,
-    credit_overflow_map: HashMap<String, f32>,
-    missing_credit_map: HashMap<String, f32>,
-    courses_overflow_map: HashMap<String, f32>,
+    credit_overflow_map: BTreeMap<String, f32>,
+    missing_credit_map: BTreeMap<String, f32>,
+    exempted_credit_map: BTreeMap<String, f32>, // The part of the missing credit of a bank which is due to exemptions without credit
+    courses_overflow_map: BTreeMap<String, f32>,
 }
 
 impl<'a> DegreeStatusHandler<'a> {
-    fn find_next_bank_with_credit_requirement(&self, bank_name: &str) -> Option<String> {
-        let find_next_bank = |bank_name: &str| {
-            self.catalog
-                .credit_overflows
-                .iter()
-                .find(|overflow| overflow.from == bank_name)
-                .and_then(|overflow| self.catalog.get_course_bank_by_name(&overflow.to))
-        };
-        let mut current_bank = bank_name.to_string();
-        while let Some(course_bank) = find_next_bank(&current_bank) {
-            if course_bank.credit.is_none() {
-                current_bank = course_bank.name.clone();
+    // Returns the banks with a credit requirement in which the excess credit of the bank may be counted, by priority.
+    // A target with a credit requirement passes the credit which doesn't fit in it to the next target,
+    // while a target without one takes all of the credit and passes it on to its own targets.
+    fn find_next_banks_with_credit_requirement(&self, bank_name: &str) -> Vec<String> {
+        let mut banks = Vec::new();
+        for credit_overflow in self.catalog.get_credit_overflows_from(bank_name) {
+            let Some(course_bank) = self.catalog.get_course_bank_by_name(&credit_overflow.to)
+            else {
+                continue;
+            };
+            if course_bank.credit.is_some() {
+                banks.push(course_bank.name.clone());
             } else {
-                return Some(course_bank.name.clone());
+                banks.extend(self.find_next_banks_with_credit_requirement(&course_bank.name));
+                break;
             }
         }
-        None
+        banks
     }
 }
 
 impl DegreeStatus {
-    pub fn compute(&mut self, mut catalog: Catalog, courses: HashMap<CourseId, Course>) {
-        let course_banks = catalog.get_bank_traversal_order();
+    pub fn compute(
+        &mut self,
+        mut catalog: Catalog,
+        courses: HashMap<CourseId, Course>,
+    ) -> Result<(), CorruptedCatalog> {
+        let course_banks =
+            catalog
+                .get_bank_traversal_order()
+                .map_err(|error| CorruptedCatalog {
+                    catalog_id: catalog.id,
+                    catalog_name: catalog.name.clone(),
+                    error,
+                })?;
 
         // prepare the data for degree status computation
         self.preprocess(&mut catalog);
+        for rules in faculty_rules::rules_for_faculty(&catalog.faculty) {
+            rules.preprocess(self, &mut catalog);
+        }
 
         DegreeStatusHandler {
             degree_status: self,
             course_banks,
             catalog: &catalog,
             courses,
-            credit_overflow_map: HashMap::new(),
-            missing_credit_map: HashMap::new(),
-            courses_overflow_map: HashMap::new(),
+            credit_overflow_map: BTreeMap::new(),
+            missing_credit_map: BTreeMap::new(),
+            exempted_credit_map: BTreeMap::new(),
+            courses_overflow_map: BTreeMap::new(),
         }
         .compute_status();
 
         // process the data after degree status computation
         self.postprocess(&catalog);
+
+        self.summarize(&catalog);
+        Ok(())
     }
 }
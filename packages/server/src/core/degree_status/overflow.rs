@@ -1,13 +1,43 @@
 use crate::{
     core::{
-        messages::{courses_overflow_msg, credit_overflow_msg, missing_credit_msg},
-        types::Transfer,
+        messages::{
+            self, converted_credit_overflow_msg, courses_overflow_msg,
+            discarded_credit_overflow_msg, exemption_transferred_credit_msg, missing_credit_msg,
+            Message,
+        },
+        types::{CreditOverflow, Transfer, TransferKind},
     },
-    resources::course::CourseBank,
+    resources::{catalog::Catalog, course::CourseBank},
 };
 
 use super::DegreeStatusHandler;
 
+fn credit_overflow_msg(
+    catalog: &Catalog,
+    rule: &CreditOverflow,
+    overflow: f32,
+    transferred: f32,
+) -> Option<Message> {
+    if transferred <= 0.0 {
+        None
+    } else if rule.ratio() != 1.0 {
+        Some(converted_credit_overflow_msg(
+            overflow,
+            transferred,
+            &rule.from,
+            &rule.to,
+        ))
+    } else {
+        catalog
+            .get_course_bank_by_name(&rule.from)
+            .and_then(|course_bank| {
+                course_bank
+                    .credit
+                    .map(|_| messages::credit_overflow_msg(transferred, &rule.from, &rule.to))
+            })
+    }
+}
+
 impl<'a> DegreeStatusHandler<'a> {
     pub fn handle_credit_overflow(
         &mut self,
@@ -44,6 +74,59 @@ impl<'a> DegreeStatusHandler<'a> {
         }
     }
 
+    // Transfers excess credit to the bank from banks which have other targets after it, up to the given capacity.
+    // Credit which doesn't fit in the bank stays in its source bank for the next target by priority.
+    // The cap of a transfer works as for any other transfer: when it is reached before the bank is full,
+    // the rest of the credit is discarded rather than passed to the next target.
+    pub fn calculate_prioritized_credit_overflows(
+        &mut self,
+        bank_name: &str,
+        mut capacity: f32,
+    ) -> f32 {
+        let mut sum = 0.0;
+        for rule in &self.catalog.credit_overflows {
+            if capacity <= 0.0 {
+                break;
+            }
+            if rule.to != bank_name || !self.catalog.is_prioritized_credit_overflow(rule) {
+                continue;
+            }
+            let Some(overflow) = self.credit_overflow_map.get_mut(&rule.from) else {
+                continue;
+            };
+            let transferred = rule.transferred_credit(*overflow).min(capacity);
+            if transferred <= 0.0 {
+                continue;
+            }
+            let capped = rule
+                .cap
+                .is_some_and(|cap| cap < (*overflow * rule.ratio()).min(capacity));
+            let discarded = if capped {
+                rule.discarded_credit(*overflow)
+            } else {
+                0.0
+            };
+            let consumed = transferred / rule.ratio();
+            *overflow -= consumed + discarded;
+            capacity -= transferred;
+            sum += transferred;
+            self.degree_status
+                .add_transfer(rule, transferred, TransferKind::Credit);
+            self.degree_status
+                .add_transfer(rule, discarded, TransferKind::DiscardedCredit);
+            if let Some(msg) = credit_overflow_msg(self.catalog, rule, consumed, transferred) {
+                self.degree_status.add_overflow_msg(msg);
+            }
+            if let (Some(cap), true) = (rule.cap, discarded > 0.0) {
+                self.degree_status
+                    .add_overflow_msg(discarded_credit_overflow_msg(
+                        discarded, cap, &rule.from, &rule.to,
+                    ));
+            }
+        }
+        sum
+    }
+
     pub fn calculate_overflows(&mut self, bank_name: &str, transfer: Transfer) -> f32 {
         let mut sum = 0.0;
         let map = match transfer {
@@ -61,26 +144,72 @@ impl<'a> DegreeStatusHandler<'a> {
             if *overflow <= 0.0 {
                 continue;
             }
-            let msg = match transfer {
-                Transfer::CreditOverflow => self
-                    .catalog
-                    .get_course_bank_by_name(&rule.from)
-                    .and_then(|course_bank| {
-                        course_bank
-                            .credit
-                            .map(|_| credit_overflow_msg(*overflow, &rule.from, &rule.to))
-                    }),
+            let (msgs, transferred) = match transfer {
+                Transfer::CreditOverflow => {
+                    // Transfers to targets which come before other targets are filled only up to their requirement,
+                    // and are handled after the target's own credit is known.
+                    if self.catalog.is_prioritized_credit_overflow(rule) {
+                        continue;
+                    }
+                    let transferred = rule.transferred_credit(*overflow);
+                    let discarded = rule.discarded_credit(*overflow);
+                    let mut msgs = Vec::new();
+                    msgs.extend(credit_overflow_msg(
+                        self.catalog,
+                        rule,
+                        *overflow - discarded,
+                        transferred,
+                    ));
+                    if let (Some(cap), true) = (rule.cap, discarded > 0.0) {
+                        msgs.push(discarded_credit_overflow_msg(
+                            discarded, cap, &rule.from, &rule.to,
+                        ));
+                    }
+                    self.degree_status
+                        .add_transfer(rule, transferred, TransferKind::Credit);
+                    self.degree_status
+                        .add_transfer(rule, discarded, TransferKind::DiscardedCredit);
+                    (msgs, transferred)
+                }
                 Transfer::CoursesOverflow => {
-                    Some(courses_overflow_msg(*overflow, &rule.from, &rule.to))
+                    self.degree_status
+                        .add_transfer(rule, *overflow, TransferKind::Courses);
+                    (
+                        vec![courses_overflow_msg(*overflow, &rule.from, &rule.to)],
+                        *overflow,
+                    )
                 }
                 Transfer::MissingCredit => {
-                    Some(missing_credit_msg(*overflow, &rule.from, &rule.to))
+                    // Credit which is missing due to exemptions is reported separately from the rest of the missing credit
+                    let exempted_credit = self
+                        .exempted_credit_map
+                        .remove(&rule.from)
+                        .unwrap_or(0.0)
+                        .min(*overflow);
+                    let mut msgs = Vec::new();
+                    if exempted_credit > 0.0 {
+                        msgs.push(exemption_transferred_credit_msg(
+                            exempted_credit,
+                            &rule.from,
+                            &rule.to,
+                        ));
+                    }
+                    if *overflow > exempted_credit {
+                        msgs.push(missing_credit_msg(
+                            *overflow - exempted_credit,
+                            &rule.from,
+                            &rule.to,
+                        ));
+                    }
+                    self.degree_status
+                        .add_transfer(rule, *overflow, TransferKind::MissingCredit);
+                    (msgs, *overflow)
                 }
             };
-            if let Some(msg) = msg {
-                self.degree_status.overflow_msgs.push(msg);
-            }
-            sum += *overflow;
+            msgs.into_iter()
+                .for_each(|msg| self.degree_status.add_overflow_msg(msg));
+            // Credit which exceeds the cap of the transfer is discarded
+            sum += transferred;
             *overflow = 0.0;
         }
         sum
@@ -1,3 +1,4 @@
+use crate::core::types::DegreeSummary;
 use crate::resources::{
     catalog::Catalog,
     course::{CourseId, CourseState},
@@ -65,7 +66,12 @@ impl DegreeStatus {
     fn reset(&mut self, catalog: &mut Catalog) {
         self.course_bank_requirements.clear();
         self.overflow_msgs.clear();
+        self.typed_overflow_msgs.clear();
+        self.transfers.clear();
         self.total_credit = 0.0;
+        self.summary = DegreeSummary::default();
+        self.secondary_statuses.clear();
+        self.shared_courses.clear();
 
         self.remove_courses_added_by_algorithm();
         self.remove_irrelevant_courses_added_by_user();
@@ -73,8 +79,18 @@ impl DegreeStatus {
         self.remove_irrelevant_courses_from_catalog(catalog);
     }
 
+    // Courses which were modified by the user keep their grade, otherwise the catalog's repetition policy decides which attempt counts
+    fn apply_repetition_policy(&mut self, catalog: &Catalog) {
+        for course_status in self.course_statuses.iter_mut() {
+            if !course_status.modified {
+                course_status.apply_repetition_policy(&catalog.repetition_policy);
+            }
+        }
+    }
+
     pub fn preprocess(&mut self, catalog: &mut Catalog) {
         self.reset(catalog);
+        self.apply_repetition_policy(catalog);
 
         self.course_statuses.sort_by(|c1, c2| {
             c1.extract_semester()
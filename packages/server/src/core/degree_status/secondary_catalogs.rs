@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::core::types::SharedCourse;
+use crate::resources::{
+    catalog::{Catalog, CourseSharing},
+    course::{Course, CourseId, CourseStatus},
+};
+
+use super::{CorruptedCatalog, DegreeStatus, SecondaryDegreeStatus};
+
+impl DegreeStatus {
+    // Returns the bank which counted the given course in the primary catalog, if any
+    fn primary_bank(&self, course_status: &CourseStatus) -> Option<String> {
+        self.course_statuses
+            .iter()
+            .find(|cs| {
+                cs.course.id == course_status.course.id
+                    && cs.semester == course_status.semester
+                    && cs.completed()
+            })
+            .and_then(|cs| cs.r#type.clone())
+    }
+
+    // Computes the status against the primary catalog, and then against each secondary catalog.
+    // A course which was counted by the primary catalog is counted by a secondary catalog only if its sharing policy allows it.
+    pub fn compute_with_secondary_catalogs(
+        &mut self,
+        catalog: Catalog,
+        secondary_catalogs: Vec<(Catalog, CourseSharing)>,
+        courses: HashMap<CourseId, Course>,
+    ) -> Result<(), CorruptedCatalog> {
+        let course_statuses = self.course_statuses.clone();
+        self.compute(catalog, courses.clone())?;
+
+        let mut secondary_statuses = Vec::new();
+        let mut shared_courses = Vec::new();
+        for (secondary_catalog, course_sharing) in secondary_catalogs {
+            let mut degree_status = DegreeStatus::default();
+            for course_status in course_statuses.iter() {
+                let primary_bank = self.primary_bank(course_status);
+                if let Some(primary_bank) = primary_bank {
+                    if !course_sharing.allows(&course_status.course.id) {
+                        // Only courses which belong to a bank of the secondary catalog are reported
+                        if secondary_catalog
+                            .course_to_bank
+                            .contains_key(&course_status.course.id)
+                        {
+                            shared_courses.push(SharedCourse {
+                                course_id: course_status.course.id.clone(),
+                                secondary_catalog: secondary_catalog.name.clone(),
+                                primary_bank,
+                                secondary_bank: None,
+                            });
+                        }
+                        continue;
+                    }
+                }
+                degree_status.course_statuses.push(course_status.clone());
+            }
+            degree_status.clear_types();
+
+            let catalog_id = secondary_catalog.id;
+            let catalog_name = secondary_catalog.name.clone();
+            degree_status.compute(secondary_catalog, courses.clone())?;
+
+            for course_status in degree_status.course_statuses.iter() {
+                if !course_status.completed() {
+                    continue;
+                }
+                if let (Some(primary_bank), Some(secondary_bank)) =
+                    (self.primary_bank(course_status), &course_status.r#type)
+                {
+                    shared_courses.push(SharedCourse {
+                        course_id: course_status.course.id.clone(),
+                        secondary_catalog: catalog_name.clone(),
+                        primary_bank,
+                        secondary_bank: Some(secondary_bank.clone()),
+                    });
+                }
+            }
+
+            secondary_statuses.push(SecondaryDegreeStatus {
+                catalog_id,
+                catalog_name,
+                degree_status,
+            });
+        }
+        self.secondary_statuses = secondary_statuses;
+        self.shared_courses = shared_courses;
+        Ok(())
+    }
+}
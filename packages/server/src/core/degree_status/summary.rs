@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+
+use crate::resources::catalog::Catalog;
+
+use super::DegreeStatus;
+
+const SUMMER_SEMESTER: &str = "קיץ";
+
+impl DegreeStatus {
+    // Returns the average credit the user completed in a semester, summer semesters are not counted as semesters.
+    fn average_credit_per_semester(&self) -> Option<f32> {
+        let completed_courses = self
+            .course_statuses
+            .iter()
+            .filter(|course_status| course_status.completed() && course_status.semester.is_some());
+        let semesters = completed_courses
+            .clone()
+            .filter_map(|course_status| course_status.semester.as_ref())
+            .filter(|semester| !semester.starts_with(SUMMER_SEMESTER))
+            .collect::<HashSet<_>>();
+        let completed_credit = completed_courses
+            .map(|course_status| course_status.course.credit)
+            .sum::<f32>();
+
+        (!semesters.is_empty() && completed_credit > 0.0)
+            .then(|| completed_credit / semesters.len() as f32)
+    }
+
+    pub fn summarize(&mut self, catalog: &Catalog) {
+        let incomplete_banks = self
+            .course_bank_requirements
+            .iter()
+            .filter(|requirement| !requirement.completed)
+            .collect::<Vec<_>>();
+
+        // The credit which is missing in the incomplete banks might be larger than the difference from the catalog's total credit,
+        // for example when the user has leftover credit.
+        let missing_credit_in_banks = incomplete_banks
+            .iter()
+            .filter_map(|requirement| {
+                requirement
+                    .credit_requirement
+                    .map(|credit_requirement| credit_requirement - requirement.credit_completed)
+            })
+            .filter(|missing_credit| *missing_credit > 0.0)
+            .sum::<f32>();
+        let total_credit = catalog.total_credit as f32 - self.summary.reduced_credit;
+        let remaining_credit = (total_credit - self.total_credit)
+            .max(missing_credit_in_banks)
+            .max(0.0);
+
+        self.summary.incomplete_banks = incomplete_banks
+            .into_iter()
+            .map(|requirement| requirement.course_bank_name.clone())
+            .collect();
+        self.summary.remaining_credit = remaining_credit;
+        self.summary.completed = remaining_credit == 0.0
+            && self.summary.incomplete_banks.is_empty()
+            && self.summary.unmet_conditions.is_empty();
+        self.summary.estimated_semesters_left = if remaining_credit == 0.0 {
+            Some(0)
+        } else {
+            self.average_credit_per_semester()
+                .map(|credit_per_semester| (remaining_credit / credit_per_semester).ceil() as usize)
+        };
+    }
+}
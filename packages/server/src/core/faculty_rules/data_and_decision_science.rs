@@ -0,0 +1,6 @@
+use super::FacultyRules;
+
+// The faculty has no demands beyond its catalogs yet
+pub struct DataAndDecisionScienceRules;
+
+impl FacultyRules for DataAndDecisionScienceRules {}
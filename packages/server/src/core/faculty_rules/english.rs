@@ -0,0 +1,72 @@
+use crate::{
+    core::{degree_status::DegreeStatus, messages, types::Requirement},
+    resources::{catalog::Catalog, course::Grade},
+};
+
+use super::FacultyRules;
+
+pub const ENGLISH_REQUIREMENT_NAME: &str = "דרישת אנגלית";
+const ENGLISH_REQUIREMENT_RULE_NAME: &str = "english requirement";
+
+pub struct EnglishRequirementRules;
+
+impl FacultyRules for EnglishRequirementRules {
+    fn postprocess(&self, degree_status: &mut DegreeStatus, catalog: &Catalog) {
+        let Some(english_requirement) = &catalog.english_requirement else {
+            return;
+        };
+        if !english_requirement.applies_to(catalog.year()) {
+            return;
+        }
+
+        let completed_english_content_courses_count = degree_status
+            .course_statuses
+            .iter()
+            .filter(|course_status| course_status.course.is_english() && course_status.completed())
+            .count();
+
+        // Determine by the level course grade kind the english level of the student.
+        // If the student didn't complete the level course it will be marked as not complete in the bank it belongs to,
+        // thus it is not necessary to add it to the important messages.
+        let level_course_grade = degree_status
+            .get_course_status(&english_requirement.level_course)
+            .filter(|course_status| course_status.completed())
+            .map(|course_status| course_status.grade);
+        let (required, msg) = match level_course_grade {
+            Some(Some(Grade::ExemptionWithoutCredit | Grade::ExemptionWithCredit)) => (
+                english_requirement.exempt_courses_count,
+                Some(messages::english_requirement_for_exempt_students_msg(
+                    english_requirement.exempt_courses_count,
+                )),
+            ),
+            Some(_) => (
+                english_requirement.courses_count,
+                Some(
+                    messages::english_requirement_for_technical_advanced_b_students_msg(
+                        english_requirement.courses_count,
+                    ),
+                ),
+            ),
+            None => (english_requirement.courses_count, None),
+        };
+
+        let completed =
+            level_course_grade.is_some() && completed_english_content_courses_count >= required;
+        let mut requirement = Requirement {
+            course_bank_name: ENGLISH_REQUIREMENT_NAME.to_string(),
+            bank_rule_name: ENGLISH_REQUIREMENT_RULE_NAME.to_string(),
+            ..Default::default()
+        };
+        requirement
+            .course_requirement(required)
+            .course_completed(completed_english_content_courses_count.min(required))
+            .completed(completed);
+
+        // The requirement is counted in the summary as an incomplete bank, so its message isn't an unmet condition
+        if let (false, Some(msg)) = (completed, msg) {
+            requirement.message(msg.clone());
+            degree_status.add_overflow_msg(msg);
+        }
+        degree_status.course_bank_requirements.push(requirement);
+    }
+}
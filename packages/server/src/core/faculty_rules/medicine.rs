@@ -0,0 +1,205 @@
+use crate::{
+    consts::medicine,
+    core::{degree_status::DegreeStatus, messages},
+    resources::{
+        catalog::{Catalog, RepetitionPolicy},
+        course::CourseStatus,
+    },
+};
+
+use super::FacultyRules;
+
+pub struct MedicineRules;
+
+// Returns the average of the courses, where each grade which counts by the catalog's repetition policy is weighted by the course credit.
+// NaN if none of the courses has a numeric grade.
+fn weighted_avg<'a>(
+    course_statuses: impl Iterator<Item = &'a CourseStatus>,
+    repetition_policy: &RepetitionPolicy,
+) -> f32 {
+    let weighted_grades = course_statuses
+        .flat_map(|course_status| {
+            course_status
+                .grades_for_average(repetition_policy)
+                .into_iter()
+                .map(|grade| (grade as f32, course_status.course.credit))
+        })
+        .collect::<Vec<_>>();
+
+    let sum_credit = weighted_grades
+        .iter()
+        .map(|(_, credit)| credit)
+        .sum::<f32>();
+
+    weighted_grades
+        .iter()
+        .map(|(grade, credit)| grade * credit)
+        .sum::<f32>()
+        / sum_credit
+}
+
+impl MedicineRules {
+    fn get_preclinical_rule_all_courses<'a>(
+        &self,
+        degree_status: &'a DegreeStatus,
+    ) -> Vec<&'a CourseStatus> {
+        degree_status.get_courses_for_bank(medicine::ALL_BANK_NAME)
+    }
+
+    fn get_all_courses_for_repetitions_violation<'a>(
+        &self,
+        degree_status: &'a DegreeStatus,
+    ) -> Vec<&'a CourseStatus> {
+        degree_status
+            .course_statuses
+            .iter()
+            .filter(|cs| {
+                cs.r#type.is_some()
+                    && !cs.course.is_medicine_clinical() // Checked by the clinical rules
+                    && cs.r#type != Some(medicine::ELECTIVE_BANK_NAME.into())
+                    && cs.r#type != Some(medicine::SPORT_BANK_NAME.into())
+            })
+            .collect::<Vec<_>>()
+    }
+
+    fn get_clinical_courses<'a>(&self, degree_status: &'a DegreeStatus) -> Vec<&'a CourseStatus> {
+        degree_status
+            .course_statuses
+            .iter()
+            .filter(|course_status| course_status.course.is_medicine_clinical())
+            .collect()
+    }
+
+    fn preclinical_avg(&self, degree_status: &DegreeStatus, catalog: &Catalog) -> f32 {
+        let highest_sport_grades = degree_status
+            .get_highest_grade_courses_up_to_credit_requirement(catalog, medicine::SPORT_BANK_NAME);
+
+        let highest_accumulated_credit_grades = degree_status
+            .get_highest_grade_courses_up_to_credit_requirement(
+                catalog,
+                medicine::FACULTY_ELECTIVE_BANK_NAME,
+            );
+
+        let highest_grade_courses = self
+            .get_preclinical_rule_all_courses(degree_status)
+            .into_iter()
+            .chain(highest_sport_grades)
+            .chain(highest_accumulated_credit_grades)
+            // The clinical courses have an average of their own
+            .filter(|course_status| !course_status.course.is_medicine_clinical());
+
+        weighted_avg(highest_grade_courses, &catalog.repetition_policy)
+    }
+
+    fn violate_course_repetitions<'a>(
+        &self,
+        course_statuses: Vec<&'a CourseStatus>,
+        limit: usize,
+    ) -> Vec<&'a CourseStatus> {
+        course_statuses
+            .into_iter()
+            .filter(|course_status| {
+                course_status.times_repeated >= limit
+                    || (course_status.times_repeated + 1 == limit
+                        && course_status.not_completed()
+                        // Ignore courses that were added by the algorithm for rule all
+                        && course_status.semester.is_some())
+            })
+            .collect()
+    }
+
+    fn total_repetitions(&self, degree_status: &DegreeStatus) -> usize {
+        self.get_all_courses_for_repetitions_violation(degree_status)
+            .into_iter()
+            .map(|course_status| course_status.times_repeated)
+            .sum()
+    }
+
+    fn preclinical_postprocess(&self, degree_status: &mut DegreeStatus, catalog: &Catalog) {
+        let policy = &catalog.medicine_policy;
+        match self.preclinical_avg(degree_status, catalog) {
+            avg if avg.is_nan() => {
+                degree_status.add_overflow_msg(messages::medicine_preclinical_avg_msg(0.))
+            }
+            avg if avg < policy.preclinical_min_avg => degree_status.add_unmet_condition(
+                messages::medicine_preclinical_avg_error_msg(avg, policy.preclinical_min_avg),
+            ),
+            avg => degree_status.add_overflow_msg(messages::medicine_preclinical_avg_msg(avg)),
+        }
+
+        let preclinical_violate_course_repetitions = self.violate_course_repetitions(
+            self.get_all_courses_for_repetitions_violation(degree_status),
+            policy.preclinical_course_repetitions_limit,
+        );
+        if !preclinical_violate_course_repetitions.is_empty() {
+            let msg = messages::medicine_preclinical_course_repetitions_error_msg(
+                preclinical_violate_course_repetitions,
+            );
+            degree_status.add_unmet_condition(msg);
+        }
+
+        let repetitions = self.total_repetitions(degree_status);
+        if repetitions >= policy.preclinical_total_repetitions_limit {
+            degree_status.add_unmet_condition(
+                messages::medicine_preclinical_total_repetitions_error_msg(
+                    repetitions,
+                    policy.preclinical_total_repetitions_limit,
+                ),
+            );
+        }
+    }
+
+    // The clinical rules are checked once the student has taken courses of the clinical years
+    fn clinical_postprocess(&self, degree_status: &mut DegreeStatus, catalog: &Catalog) {
+        let policy = &catalog.medicine_policy;
+        let clinical_courses = self.get_clinical_courses(degree_status);
+        if clinical_courses.is_empty() {
+            return;
+        }
+
+        let avg = weighted_avg(clinical_courses.iter().copied(), &catalog.repetition_policy);
+        let violate_course_repetitions = self
+            .violate_course_repetitions(clinical_courses, policy.clinical_course_repetitions_limit);
+        let repetitions_msg = (!violate_course_repetitions.is_empty()).then(|| {
+            messages::medicine_clinical_course_repetitions_error_msg(violate_course_repetitions)
+        });
+
+        let incomplete_rotations = policy
+            .clinical_rotations
+            .iter()
+            .filter_map(
+                |course_id| match degree_status.get_course_status(course_id) {
+                    Some(course_status) if course_status.completed() => None,
+                    Some(course_status) => Some(course_status.course.name.clone()),
+                    None => Some(course_id.clone()),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        if avg.is_nan() {
+            // None of the clinical courses has a numeric grade yet
+        } else if avg < policy.clinical_min_avg {
+            degree_status.add_unmet_condition(messages::medicine_clinical_avg_error_msg(
+                avg,
+                policy.clinical_min_avg,
+            ));
+        } else {
+            degree_status.add_overflow_msg(messages::medicine_clinical_avg_msg(avg));
+        }
+        if let Some(msg) = repetitions_msg {
+            degree_status.add_unmet_condition(msg);
+        }
+        if !incomplete_rotations.is_empty() {
+            degree_status.add_unmet_condition(messages::medicine_incomplete_rotations_msg(
+                incomplete_rotations,
+            ));
+        }
+    }
+}
+
+impl FacultyRules for MedicineRules {
+    fn postprocess(&self, degree_status: &mut DegreeStatus, catalog: &Catalog) {
+        self.preclinical_postprocess(degree_status, catalog);
+        self.clinical_postprocess(degree_status, catalog);
+    }
+}
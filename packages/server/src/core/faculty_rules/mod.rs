@@ -0,0 +1,33 @@
+pub mod data_and_decision_science;
+pub mod english;
+pub mod medicine;
+
+use crate::core::degree_status::DegreeStatus;
+use crate::resources::catalog::{Catalog, Faculty};
+
+use self::{
+    data_and_decision_science::DataAndDecisionScienceRules, english::EnglishRequirementRules,
+    medicine::MedicineRules,
+};
+
+// Demands of a faculty which are not expressed by the course banks of its catalogs.
+// A new faculty plugs in by implementing the hooks it needs and adding itself to `rules_for_faculty`.
+pub trait FacultyRules {
+    // Called before the degree status is computed
+    fn preprocess(&self, _degree_status: &mut DegreeStatus, _catalog: &mut Catalog) {}
+
+    // Called after the degree status is computed, for checks which may add messages and unmet conditions
+    fn postprocess(&self, _degree_status: &mut DegreeStatus, _catalog: &Catalog) {}
+}
+
+// Returns the rules which apply to the catalogs of the faculty, in the order they should be applied
+pub fn rules_for_faculty(faculty: &Faculty) -> Vec<Box<dyn FacultyRules>> {
+    // The english requirement applies to all faculties
+    let mut rules: Vec<Box<dyn FacultyRules>> = vec![Box::new(EnglishRequirementRules)];
+    match faculty {
+        Faculty::Medicine => rules.push(Box::new(MedicineRules)),
+        Faculty::DataAndDecisionScience => rules.push(Box::new(DataAndDecisionScienceRules)),
+        Faculty::ComputerScience | Faculty::Unknown => {}
+    }
+    rules
+}
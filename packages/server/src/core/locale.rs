@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+// The language of the text which is displayed to the user. Stored documents are always rendered in Hebrew.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    Hebrew,
+    English,
+}
+
+thread_local! {
+    static SERIALIZATION_LANGUAGE: Cell<Language> = const { Cell::new(Language::Hebrew) };
+}
+
+// Runs the serialization in `f` with course states and grades rendered in the given language.
+// Only the typed labels are localized: free text such as bank and course names stays in Hebrew,
+// as does anything which is serialized outside of `f`, e.g documents which are written to the database.
+pub fn serialize_in<T>(language: Language, f: impl FnOnce() -> T) -> T {
+    // Restores the previous language even if `f` panics, so later requests on the thread aren't affected
+    struct Restore(Language);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SERIALIZATION_LANGUAGE.with(|cell| cell.set(self.0));
+        }
+    }
+    let _restore = Restore(SERIALIZATION_LANGUAGE.with(|cell| cell.replace(language)));
+    f()
+}
+
+// The language in which course states and grades are currently serialized
+pub fn serialization_language() -> Language {
+    SERIALIZATION_LANGUAGE.with(Cell::get)
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Hebrew, Language::English];
+
+    // Returns the supported language with the highest weight in an Accept-Language header, for example "en-US,en;q=0.9,he;q=0.8"
+    pub fn from_accept_language(header: &str) -> Option<Language> {
+        let mut languages = header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.trim().split(';');
+                let tag = parts.next()?.trim();
+                let weight = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .and_then(|weight| weight.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((tag, weight))
+            })
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect::<Vec<_>>();
+        languages.sort_by(|(_, w1), (_, w2)| w2.total_cmp(w1));
+        languages.into_iter().find_map(|(tag, _)| {
+            match tag.split('-').next()?.to_lowercase().as_str() {
+                "he" | "iw" => Some(Language::Hebrew),
+                "en" => Some(Language::English),
+                _ => None,
+            }
+        })
+    }
+}
@@ -1,164 +1,716 @@
 use std::fmt::Write;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     consts::medicine,
-    resources::course::{Course, CourseStatus},
+    core::locale::Language,
+    resources::course::{Course, CourseId, CourseStatus},
 };
 
 const ZERO: f32 = 0.0;
 const HALF: f32 = 0.5;
 const SINGLE: f32 = 1.0;
 
-pub fn common_replacements_msg(course: &Course) -> String {
-    format!(
-        "הנחנו כי קורס זה מחליף את הקורס {} ({}) בעקבות החלפות נפוצות. שימו לב כי נדרש אישור מהרכזות בשביל החלפה זו",
-        course.name,
-        course.id
-    )
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RepeatedCourse {
+    pub course_id: CourseId,
+    pub course_name: String,
+    pub times_repeated: usize,
+}
+
+// A message which is displayed to the user. It is serialized with a stable code and its parameters,
+// so clients can style, filter and translate it, and it is rendered in Hebrew for clients which display it as is.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "code", content = "params", rename_all = "snake_case")]
+pub enum Message {
+    CommonReplacement {
+        course_id: CourseId,
+        course_name: String,
+    },
+    CatalogReplacement {
+        course_id: CourseId,
+        course_name: String,
+    },
+    CreditOverflow {
+        credit: f32,
+        from: String,
+        to: String,
+    },
+    ConvertedCreditOverflow {
+        overflow: f32,
+        transferred: f32,
+        from: String,
+        to: String,
+    },
+    DiscardedCreditOverflow {
+        discarded: f32,
+        cap: f32,
+        from: String,
+        to: String,
+    },
+    CreditOverflowDetailed {
+        from: String,
+        to: String,
+    },
+    CoursesOverflow {
+        courses: f32,
+        from: String,
+        to: String,
+    },
+    MissingCredit {
+        credit: f32,
+        from: String,
+        to: String,
+    },
+    ExemptionTransferredCredit {
+        credit: f32,
+        from: String,
+        to: String,
+    },
+    ExemptionReducedCredit {
+        credit: f32,
+        bank_name: String,
+    },
+    CompletedChain {
+        chain: Vec<String>,
+    },
+    CompletedSpecializationGroups {
+        groups: Vec<String>,
+        needed: usize,
+    },
+    CreditLeftovers {
+        credit: f32,
+    },
+    EnglishRequirementForExemptStudents {
+        required: usize,
+    },
+    EnglishRequirementForTechnicalAdvancedBStudents {
+        required: usize,
+    },
+    MedicinePreclinicalAvgError {
+        avg: f32,
+        #[serde(default = "preclinical_min_avg")]
+        min_avg: f32,
+    },
+    MedicinePreclinicalAvg {
+        avg: f32,
+    },
+    MedicinePreclinicalCourseRepetitionsError {
+        courses: Vec<RepeatedCourse>,
+    },
+    MedicinePreclinicalTotalRepetitionsError {
+        repetitions: usize,
+        #[serde(default = "preclinical_total_repetitions_limit")]
+        limit: usize,
+    },
+    MedicineClinicalAvgError {
+        avg: f32,
+        min_avg: f32,
+    },
+    MedicineClinicalAvg {
+        avg: f32,
+    },
+    MedicineClinicalCourseRepetitionsError {
+        courses: Vec<RepeatedCourse>,
+    },
+    MedicineIncompleteRotations {
+        rotations: Vec<String>,
+    },
 }
 
-pub fn catalog_replacements_msg(course: &Course) -> String {
-    format!("קורס זה מחליף את הקורס {} ({})", course.name, course.id)
+// Messages which were stored before the threshold became part of the catalog
+fn preclinical_min_avg() -> f32 {
+    medicine::PRECLINICAL_MIN_AVG
 }
 
-pub fn credit_overflow_msg(overflow: f32, from: &str, to: &str) -> String {
-    if overflow == SINGLE {
-        format!("נקודה אחת עברה מ{from} ל{to}")
-    } else if overflow == HALF {
-        format!("חצי נקודה עברה מ{from} ל{to}")
-    } else {
-        format!("עברו {overflow} נקודות מ{from} ל{to}")
+fn preclinical_total_repetitions_limit() -> usize {
+    medicine::PRECLINICAL_TOTAL_REPETITIONS_LIMIT
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.render(Language::Hebrew))
     }
 }
 
-pub fn credit_overflow_detailed_msg(from: &str, to: &str) -> String {
-    format!("הנקודות שבוצעו ב{from} נספרות תחת {to}")
+// Formats a list of items the way the Hebrew messages do, the last item first
+fn reversed_list(items: &[String]) -> String {
+    items.iter().rev().cloned().collect::<Vec<_>>().join(", ")
 }
 
-pub fn courses_overflow_msg(overflow: f32, from: &str, to: &str) -> String {
-    if overflow == SINGLE {
-        format!("ביצעת יותר קורסים ממה שנדרש ב{from}, הקורס העודף נספר תחת הדרישה {to}")
-    } else {
-        format!(
-            "ביצעת יותר קורסים ממה שנדרש ב{from}, {overflow} הקורסים העודפים נספרים תחת הדרישה {to}"
-        )
+fn english_content_courses(count: usize) -> String {
+    match count {
+        1 => "a content course in English".to_string(),
+        count => format!("{count} content courses in English"),
     }
 }
 
-pub fn missing_credit_msg(overflow: f32, from: &str, to: &str) -> String {
-    if overflow == SINGLE {
-        format!(
-            "סך הנקודות של הקורסים שלקחת ב{from} נמוך מהדרישה המקורית, לכן נקודה אחת התווספה לדרישה של {to}"
-        )
-    } else {
-        format!(
-            "סך הנקודות של הקורסים שלקחת ב{from} נמוך מהדרישה המקורית, לכן {overflow} נקודות התווספו לדרישה של {to}"
-        )
+fn hebrew_english_content_courses(count: usize) -> String {
+    match count {
+        1 => "קורס תוכן באנגלית".to_string(),
+        2 => "שני קורסי תוכן באנגלית".to_string(),
+        count => format!("{count} קורסי תוכן באנגלית"),
     }
 }
 
-pub fn completed_chain_msg(mut chain: Vec<String>) -> String {
-    let mut msg = "השלמת את השרשרת: ".to_string();
-    while let Some(course) = chain.pop() {
-        if chain.is_empty() {
-            msg += &course;
-        } else {
-            let _ = write!(msg, "{course}, ");
+impl Message {
+    pub fn render(&self, language: Language) -> String {
+        match language {
+            Language::Hebrew => self.hebrew(),
+            Language::English => self.english(),
         }
     }
-    msg
-}
-
-pub fn completed_specialization_groups_msg(mut groups: Vec<String>, needed: usize) -> String {
-    let mut msg = if groups.len() == ZERO as usize {
-        "לא השלמת אף קבוצת התמחות".to_string()
-    } else if groups.len() == SINGLE as usize {
-        format!("השלמת קבוצת התמחות אחת (מתוך {needed}): ")
-    } else {
-        format!("השלמת {} (מתוך {}) קבוצות התמחות: ", groups.len(), needed)
-    };
-    while let Some(group) = groups.pop() {
-        if groups.is_empty() {
-            msg += &group;
-        } else {
-            let _ = write!(msg, "{group}, ");
+
+    fn english(&self) -> String {
+        let credit_points = |credit: &f32| {
+            if *credit == SINGLE {
+                "1 credit point".to_string()
+            } else {
+                format!("{credit} credit points")
+            }
+        };
+        match self {
+            Message::CommonReplacement {
+                course_id,
+                course_name,
+            } => format!(
+                "We assumed this course replaces the course {} ({}) since it is a common replacement. Note that this replacement requires the approval of the coordinators",
+                course_name, course_id
+            ),
+            Message::CatalogReplacement {
+                course_id,
+                course_name,
+            } => format!("This course replaces the course {} ({})", course_name, course_id),
+            Message::CreditOverflow { credit, from, to } => {
+                format!("{} moved from {from} to {to}", credit_points(credit))
+            }
+            Message::ConvertedCreditOverflow {
+                overflow,
+                transferred,
+                from,
+                to,
+            } => format!(
+                "{} of excess credit from {from} counted as {} in {to}",
+                credit_points(overflow),
+                credit_points(transferred)
+            ),
+            Message::DiscardedCreditOverflow {
+                discarded,
+                cap,
+                from,
+                to,
+            } => format!(
+                "At most {} may move from {from} to {to}, so {} of excess credit were not counted",
+                credit_points(cap),
+                credit_points(discarded)
+            ),
+            Message::CreditOverflowDetailed { from, to } => {
+                format!("The credit of {from} is counted under {to}")
+            }
+            Message::CoursesOverflow { courses, from, to } => {
+                if *courses == SINGLE {
+                    format!("You completed more courses than required in {from}, the extra course is counted under {to}")
+                } else {
+                    format!("You completed more courses than required in {from}, the {courses} extra courses are counted under {to}")
+                }
+            }
+            Message::MissingCredit { credit, from, to } => format!(
+                "The credit of the courses you took in {from} is lower than the original requirement, so {} were added to the requirement of {to}",
+                credit_points(credit)
+            ),
+            Message::ExemptionTransferredCredit { credit, from, to } => format!(
+                "Due to exemptions without credit in {from}, {} were added to the requirement of {to}",
+                credit_points(credit)
+            ),
+            Message::ExemptionReducedCredit { credit, bank_name } => format!(
+                "Due to exemptions without credit in {bank_name}, the credit requirement of the degree was reduced by {}",
+                credit_points(credit)
+            ),
+            Message::CompletedChain { chain } => {
+                format!("You completed the chain: {}", reversed_list(chain))
+            }
+            Message::CompletedSpecializationGroups { groups, needed } => {
+                if groups.is_empty() {
+                    "You did not complete any specialization group".to_string()
+                } else {
+                    format!(
+                        "You completed {} (out of {}) specialization groups: {}",
+                        groups.len(),
+                        needed,
+                        reversed_list(groups)
+                    )
+                }
+            }
+            Message::CreditLeftovers { credit } => {
+                if *credit == ZERO {
+                    "You have no excess credit".to_string()
+                } else {
+                    format!("You have {} of excess credit", credit_points(credit))
+                }
+            }
+            Message::EnglishRequirementForExemptStudents { required } => format!(
+                "Warning: you did not complete the English requirement of the degree. Students of your catalog must complete {}.",
+                english_content_courses(*required)
+            ),
+            Message::EnglishRequirementForTechnicalAdvancedBStudents { required } => format!(
+                "Warning: you did not complete the English requirement of the degree. Students of your catalog must complete {} in addition to Technical English Advanced B.",
+                english_content_courses(*required)
+            ),
+            Message::MedicinePreclinicalAvgError { avg, min_avg } => format!(
+                "Disqualification: the average grade of the medicine courses you took is {:.2}. Continuing your studies requires an average higher than {}. Please contact the year advisor and the student coordinator urgently.",
+                avg, min_avg
+            ),
+            Message::MedicinePreclinicalAvg { avg } => {
+                format!("The average grade of the medicine courses you took is {:.2}", avg)
+            }
+            Message::MedicinePreclinicalCourseRepetitionsError { courses } => {
+                if courses.len() == 1 && courses[0].times_repeated == 1 {
+                    format!(
+                        "Disqualification: you repeated the course \"{}\", please contact the year advisor and the student coordinator urgently.",
+                        courses[0].course_name
+                    )
+                } else {
+                    format!(
+                        "Disqualification: you failed the courses: {}. Please contact the year advisor and the student coordinator urgently.",
+                        courses
+                            .iter()
+                            .map(|course| format!("\"{}\"", course.course_name))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                }
+            }
+            Message::MedicinePreclinicalTotalRepetitionsError { repetitions, limit } => format!(
+                "Disqualification: in total, you repeated courses {repetitions} times, while it is not allowed to repeat courses more than {} times",
+                limit.saturating_sub(1)
+            ),
+            Message::MedicineClinicalAvgError { avg, min_avg } => format!(
+                "Warning: the average grade of the clinical courses you took is {:.2}, while the clinical years require an average of at least {}. Please contact the year advisor.",
+                avg, min_avg
+            ),
+            Message::MedicineClinicalAvg { avg } => {
+                format!("The average grade of the clinical courses you took is {:.2}", avg)
+            }
+            Message::MedicineClinicalCourseRepetitionsError { courses } => format!(
+                "Warning: you exceeded the repetitions allowed in the clinical courses: {}. Please contact the year advisor.",
+                courses
+                    .iter()
+                    .map(|course| format!("\"{}\"", course.course_name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Message::MedicineIncompleteRotations { rotations } => format!(
+                "You did not complete the clinical rotations: {}",
+                rotations.join(", ")
+            ),
+        }
+    }
+
+    fn hebrew(&self) -> String {
+        match self {
+            Message::CommonReplacement {
+                course_id,
+                course_name,
+            } => format!(
+                "הנחנו כי קורס זה מחליף את הקורס {} ({}) בעקבות החלפות נפוצות. שימו לב כי נדרש אישור מהרכזות בשביל החלפה זו",
+                course_name,
+                course_id
+            ),
+            Message::CatalogReplacement {
+                course_id,
+                course_name,
+            } => format!("קורס זה מחליף את הקורס {} ({})", course_name, course_id),
+            Message::CreditOverflow { credit, from, to } => {
+                if *credit == SINGLE {
+                    format!("נקודה אחת עברה מ{from} ל{to}")
+                } else if *credit == HALF {
+                    format!("חצי נקודה עברה מ{from} ל{to}")
+                } else {
+                    format!("עברו {credit} נקודות מ{from} ל{to}")
+                }
+            }
+            Message::ConvertedCreditOverflow {
+                overflow,
+                transferred,
+                from,
+                to,
+            } => format!("{overflow} נקודות עודפות מ{from} נספרו כ-{transferred} נקודות ב{to}"),
+            Message::DiscardedCreditOverflow {
+                discarded,
+                cap,
+                from,
+                to,
+            } => {
+                if *discarded == SINGLE {
+                    format!("ניתן להעביר עד {cap} נקודות מ{from} ל{to}, לכן נקודה עודפת אחת לא נספרה")
+                } else {
+                    format!("ניתן להעביר עד {cap} נקודות מ{from} ל{to}, לכן {discarded} נקודות עודפות לא נספרו")
+                }
+            }
+            Message::CreditOverflowDetailed { from, to } => {
+                format!("הנקודות שבוצעו ב{from} נספרות תחת {to}")
+            }
+            Message::CoursesOverflow { courses, from, to } => {
+                if *courses == SINGLE {
+                    format!("ביצעת יותר קורסים ממה שנדרש ב{from}, הקורס העודף נספר תחת הדרישה {to}")
+                } else {
+                    format!(
+                        "ביצעת יותר קורסים ממה שנדרש ב{from}, {courses} הקורסים העודפים נספרים תחת הדרישה {to}"
+                    )
+                }
+            }
+            Message::MissingCredit { credit, from, to } => {
+                if *credit == SINGLE {
+                    format!(
+                        "סך הנקודות של הקורסים שלקחת ב{from} נמוך מהדרישה המקורית, לכן נקודה אחת התווספה לדרישה של {to}"
+                    )
+                } else {
+                    format!(
+                        "סך הנקודות של הקורסים שלקחת ב{from} נמוך מהדרישה המקורית, לכן {credit} נקודות התווספו לדרישה של {to}"
+                    )
+                }
+            }
+            Message::ExemptionTransferredCredit { credit, from, to } => {
+                if *credit == SINGLE {
+                    format!("בעקבות פטור ללא ניקוד בקורסים ב{from}, נקודה אחת התווספה לדרישה של {to}")
+                } else {
+                    format!("בעקבות פטור ללא ניקוד בקורסים ב{from}, {credit} נקודות התווספו לדרישה של {to}")
+                }
+            }
+            Message::ExemptionReducedCredit { credit, bank_name } => {
+                if *credit == SINGLE {
+                    format!("בעקבות פטור ללא ניקוד בקורסים ב{bank_name}, דרישת הנקודות לתואר הופחתה בנקודה אחת")
+                } else {
+                    format!("בעקבות פטור ללא ניקוד בקורסים ב{bank_name}, דרישת הנקודות לתואר הופחתה ב-{credit} נקודות")
+                }
+            }
+            Message::CompletedChain { chain } => {
+                let mut chain = chain.clone();
+                let mut msg = "השלמת את השרשרת: ".to_string();
+                while let Some(course) = chain.pop() {
+                    if chain.is_empty() {
+                        msg += &course;
+                    } else {
+                        let _ = write!(msg, "{course}, ");
+                    }
+                }
+                msg
+            }
+            Message::CompletedSpecializationGroups { groups, needed } => {
+                let mut groups = groups.clone();
+                let mut msg = if groups.len() == ZERO as usize {
+                    "לא השלמת אף קבוצת התמחות".to_string()
+                } else if groups.len() == SINGLE as usize {
+                    format!("השלמת קבוצת התמחות אחת (מתוך {needed}): ")
+                } else {
+                    format!("השלמת {} (מתוך {}) קבוצות התמחות: ", groups.len(), needed)
+                };
+                while let Some(group) = groups.pop() {
+                    if groups.is_empty() {
+                        msg += &group;
+                    } else {
+                        let _ = write!(msg, "{group}, ");
+                    }
+                }
+                msg
+            }
+            Message::CreditLeftovers { credit } => {
+                if *credit == ZERO {
+                    "אין לך נקודות עודפות".to_string()
+                } else if *credit == SINGLE {
+                    "יש לך נקודה עודפת אחת".to_string()
+                } else if *credit == HALF {
+                    "יש לך חצי נקודה עודפת".to_string()
+                } else {
+                    format!("יש לך {credit} נקודות עודפות")
+                }
+            }
+            Message::EnglishRequirementForExemptStudents { required } => format!(
+                "אזהרה: לא השלמת את דרישת האנגלית לסיום התואר. סטודנטים בקטלוג שלך נדרשים להשלים {}.",
+                hebrew_english_content_courses(*required)
+            ),
+            Message::EnglishRequirementForTechnicalAdvancedBStudents { required } => format!(
+                "אזהרה: לא השלמת את דרישת האנגלית לסיום התואר. סטודנטים בקטלוג שלך נדרשים להשלים {} בנוסף לקורס אנגלית טכנית מתקדמים ב",
+                hebrew_english_content_courses(*required)
+            ),
+            Message::MedicinePreclinicalAvgError { avg, min_avg } => format!(
+                "פסילה: ממוצע הציונים של קורסי הרפואה שלקחת הוא {:.2}. המשך הלימודים מותנה בשמירה על ממוצע גבוה מ-{}. יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
+                avg, min_avg
+            ),
+            Message::MedicinePreclinicalAvg { avg } => {
+                format!("ממוצע הציונים של קורסי הרפואה שלקחת הוא {:.2}", avg)
+            }
+            Message::MedicinePreclinicalCourseRepetitionsError { courses } => {
+                if courses.len() == 1 {
+                    if courses[0].times_repeated == 1 {
+                        format!(
+                            "פסילה: חזרת על הקורס \"{}\", יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
+                            courses[0].course_name
+                        )
+                    } else {
+                        format!(
+                            "פסילה: נכשלת בקורס \"{}\", יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
+                            courses[0].course_name
+                        )
+                    }
+                } else {
+                    let mut msg = "פסילה: נכשלת בקורסים: ".to_string();
+                    courses.iter().for_each(|course| {
+                        msg += &format!("\"{}\", ", course.course_name);
+                    });
+                    msg += " יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.";
+                    msg
+                }
+            }
+            Message::MedicinePreclinicalTotalRepetitionsError { repetitions, limit } => format!(
+                "פסילה: סך הכל, חזרת על קורסים {repetitions} פעמים, בעוד שלא ניתן לחזור על קורסים יותר מ-{} פעמים",
+                limit.saturating_sub(1)
+            ),
+            Message::MedicineClinicalAvgError { avg, min_avg } => format!(
+                "אזהרה: ממוצע הציונים של הקורסים הקליניים שלקחת הוא {:.2}, בעוד שהשנים הקליניות מחייבות ממוצע של לפחות {}. יש ליצור קשר עם יועץ השנה.",
+                avg, min_avg
+            ),
+            Message::MedicineClinicalAvg { avg } => {
+                format!("ממוצע הציונים של הקורסים הקליניים שלקחת הוא {:.2}", avg)
+            }
+            Message::MedicineClinicalCourseRepetitionsError { courses } => format!(
+                "אזהרה: חרגת ממספר החזרות המותר בקורסים הקליניים: {}. יש ליצור קשר עם יועץ השנה.",
+                reversed_list(
+                    &courses
+                        .iter()
+                        .map(|course| format!("\"{}\"", course.course_name))
+                        .collect::<Vec<_>>()
+                )
+            ),
+            Message::MedicineIncompleteRotations { rotations } => {
+                format!("לא השלמת את הסבבים הקליניים: {}", reversed_list(rotations))
+            }
         }
     }
-    msg
 }
 
-pub fn credit_leftovers_msg(credit: f32) -> String {
-    if credit == ZERO {
-        "אין לך נקודות עודפות".to_string()
-    } else if credit == SINGLE {
-        "יש לך נקודה עודפת אחת".to_string()
-    } else if credit == HALF {
-        "יש לך חצי נקודה עודפת".to_string()
-    } else {
-        format!("יש לך {credit} נקודות עודפות")
+pub fn common_replacements_msg(course: &Course) -> Message {
+    Message::CommonReplacement {
+        course_id: course.id.clone(),
+        course_name: course.name.clone(),
     }
 }
 
-pub fn english_requirement_for_exempt_students_msg() -> String {
-    "אזהרה: לא השלמת את דרישת האנגלית לסיום התואר. סטודנטים שהתחילו את לימודיהם החל מתשפ\"ב נדרשים להשלים שני קורסי תוכן באנגלית.".to_string()
+pub fn catalog_replacements_msg(course: &Course) -> Message {
+    Message::CatalogReplacement {
+        course_id: course.id.clone(),
+        course_name: course.name.clone(),
+    }
 }
 
-pub fn english_requirement_for_technical_advanced_b_students_msg() -> String {
-    "אזהרה: לא השלמת את דרישת האנגלית לסיום התואר. סטודנטים שהתחילו את לימודיהם החל מתשפ\"ב נדרשים להשלים קורס תוכן באנגלית בנוסף לקורס אנגלית טכנית מתקדמים ב ".to_string()
+pub fn credit_overflow_msg(overflow: f32, from: &str, to: &str) -> Message {
+    Message::CreditOverflow {
+        credit: overflow,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
 }
 
-pub fn medicine_preclinical_avg_error_msg(avg: f32) -> String {
-    format!(
-        "פסילה: ממוצע הציונים של קורסי הרפואה שלקחת הוא {:.2}. המשך הלימודים מותנה בשמירה על ממוצע גבוה מ-{}. יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
-        avg, medicine::PRECLINICAL_MIN_AVG
-    )
+pub fn converted_credit_overflow_msg(
+    overflow: f32,
+    transferred: f32,
+    from: &str,
+    to: &str,
+) -> Message {
+    Message::ConvertedCreditOverflow {
+        overflow,
+        transferred,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+pub fn discarded_credit_overflow_msg(discarded: f32, cap: f32, from: &str, to: &str) -> Message {
+    Message::DiscardedCreditOverflow {
+        discarded,
+        cap,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+pub fn credit_overflow_detailed_msg(from: &str, to: &str) -> Message {
+    Message::CreditOverflowDetailed {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
 }
 
-pub fn medicine_preclinical_avg_msg(avg: f32) -> String {
-    format!("ממוצע הציונים של קורסי הרפואה שלקחת הוא {:.2}", avg)
+pub fn courses_overflow_msg(overflow: f32, from: &str, to: &str) -> Message {
+    Message::CoursesOverflow {
+        courses: overflow,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+pub fn missing_credit_msg(overflow: f32, from: &str, to: &str) -> Message {
+    Message::MissingCredit {
+        credit: overflow,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+pub fn exemption_transferred_credit_msg(credit: f32, from: &str, to: &str) -> Message {
+    Message::ExemptionTransferredCredit {
+        credit,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+pub fn exemption_reduced_credit_msg(credit: f32, bank_name: &str) -> Message {
+    Message::ExemptionReducedCredit {
+        credit,
+        bank_name: bank_name.to_string(),
+    }
+}
+
+pub fn completed_chain_msg(chain: Vec<String>) -> Message {
+    Message::CompletedChain { chain }
+}
+
+pub fn completed_specialization_groups_msg(groups: Vec<String>, needed: usize) -> Message {
+    Message::CompletedSpecializationGroups { groups, needed }
+}
+
+pub fn credit_leftovers_msg(credit: f32) -> Message {
+    Message::CreditLeftovers { credit }
+}
+
+pub fn english_requirement_for_exempt_students_msg(required: usize) -> Message {
+    Message::EnglishRequirementForExemptStudents { required }
+}
+
+pub fn english_requirement_for_technical_advanced_b_students_msg(required: usize) -> Message {
+    Message::EnglishRequirementForTechnicalAdvancedBStudents { required }
+}
+
+pub fn medicine_preclinical_avg_error_msg(avg: f32, min_avg: f32) -> Message {
+    Message::MedicinePreclinicalAvgError { avg, min_avg }
+}
+
+pub fn medicine_preclinical_avg_msg(avg: f32) -> Message {
+    Message::MedicinePreclinicalAvg { avg }
+}
+
+fn repeated_courses(course_statuses: Vec<&CourseStatus>) -> Vec<RepeatedCourse> {
+    course_statuses
+        .into_iter()
+        .map(|course_status| RepeatedCourse {
+            course_id: course_status.course.id.clone(),
+            course_name: course_status.course.name.clone(),
+            times_repeated: course_status.times_repeated,
+        })
+        .collect()
 }
 
 pub fn medicine_preclinical_course_repetitions_error_msg(
     course_statuses: Vec<&CourseStatus>,
-) -> String {
-    if course_statuses.len() == 1 {
-        if course_statuses[0].times_repeated == 1 {
-            format!(
-                "פסילה: חזרת על הקורס \"{}\", יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
-                course_statuses[0].course.name
-            )
-        } else {
-            format!(
-                "פסילה: נכשלת בקורס \"{}\", יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.",
-                course_statuses[0].course.name
-            )
-        }
-    } else {
-        let mut msg = "פסילה: נכשלת בקורסים: ".to_string();
-        course_statuses.iter().for_each(|course_status| {
-            msg += &format!("\"{}\", ", course_status.course.name);
-        });
-        msg += " יש ליצור קשר בדחיפות עם יועץ השנה ורכזת הסטודנטים.";
-        msg
+) -> Message {
+    Message::MedicinePreclinicalCourseRepetitionsError {
+        courses: repeated_courses(course_statuses),
+    }
+}
+
+pub fn medicine_preclinical_total_repetitions_error_msg(
+    repetitions: usize,
+    limit: usize,
+) -> Message {
+    Message::MedicinePreclinicalTotalRepetitionsError { repetitions, limit }
+}
+
+pub fn medicine_clinical_avg_error_msg(avg: f32, min_avg: f32) -> Message {
+    Message::MedicineClinicalAvgError { avg, min_avg }
+}
+
+pub fn medicine_clinical_avg_msg(avg: f32) -> Message {
+    Message::MedicineClinicalAvg { avg }
+}
+
+pub fn medicine_clinical_course_repetitions_error_msg(
+    course_statuses: Vec<&CourseStatus>,
+) -> Message {
+    Message::MedicineClinicalCourseRepetitionsError {
+        courses: repeated_courses(course_statuses),
     }
 }
 
-pub fn medicine_preclinical_total_repetitions_error_msg(repetitions: usize) -> String {
-    format!("פסילה: סך הכל, חזרת על קורסים {repetitions} פעמים. לא ניתן לחזור על יותר משני קורסים, או לחזור על קורס אחד יותר מפעמיים")
+pub fn medicine_incomplete_rotations_msg(rotations: Vec<String>) -> Message {
+    Message::MedicineIncompleteRotations { rotations }
 }
 
 /////////////////////////////////////////////////////////////////////////////////
-/// Error messages
+// Error messages
 /////////////////////////////////////////////////////////////////////////////////
 
 pub fn cyclic_credit_transfer_graph(bank_in_cycle: &str) -> String {
     format!("קיימת תלות מעגלית במעברי הנקודות שנקבעו. התלות המעגלית מתחילה ונגמרת ב{bank_in_cycle}")
 }
 
+pub fn unknown_bank_for_course(course_id: &str, bank_name: &str) -> String {
+    format!("הקורס {course_id} משויך לבנק {bank_name} שאינו קיים בקטלוג")
+}
+
+pub fn unknown_bank_for_credit_overflow(from: &str, to: &str, bank_name: &str) -> String {
+    format!("מעבר הנקודות מ{from} ל{to} מתייחס לבנק {bank_name} שאינו קיים בקטלוג")
+}
+
+pub fn invalid_credit_overflow_ratio(from: &str, to: &str, ratio: f32) -> String {
+    format!("יחס ההמרה של מעבר הנקודות מ{from} ל{to} הוא {ratio}, אך עליו להיות חיובי")
+}
+
+pub fn invalid_credit_overflow_cap(from: &str, to: &str, cap: f32) -> String {
+    format!("התקרה של מעבר הנקודות מ{from} ל{to} היא {cap}, אך היא אינה יכולה להיות שלילית")
+}
+
+pub fn duplicate_credit_overflow(from: &str, to: &str) -> String {
+    format!("מעבר הנקודות מ{from} ל{to} מופיע יותר מפעם אחת")
+}
+
+pub fn course_not_in_bank(course_id: &str, bank_name: &str) -> String {
+    format!("הקורס {course_id} מופיע בחוקי הבנק {bank_name} אך אינו משויך לבנק")
+}
+
+pub fn too_many_required_specialization_groups(
+    bank_name: &str,
+    groups_number: usize,
+    groups_count: usize,
+) -> String {
+    format!("בבנק {bank_name} נדרשות {groups_number} קבוצות התמחות אך קיימות רק {groups_count}")
+}
+
+pub fn mandatory_course_not_in_specialization_group(course_id: &str, group_name: &str) -> String {
+    format!("קורס החובה {course_id} אינו מופיע ברשימת הקורסים של קבוצת ההתמחות {group_name}")
+}
+
+pub fn banks_credit_mismatch(banks_credit: f32, total_credit: f32) -> String {
+    format!("סך הנקודות של הבנקים ({banks_credit}) שונה מסך הנקודות של הקטלוג ({total_credit})")
+}
+
+pub fn unknown_course_in_catalog(course_id: &str) -> String {
+    format!("הקורס {course_id} אינו קיים במאגר הקורסים")
+}
+
+pub fn unknown_course_in_replacement(course_id: &str, replacement: &str) -> String {
+    format!("הקורס {replacement}, המחליף את הקורס {course_id}, אינו קיים במאגר הקורסים")
+}
+
 pub fn build_credit_transfer_graph_failed() -> String {
     "בניית הגרף נכשלה".to_string()
 }
 
+pub fn corrupted_catalog(catalog_name: &str, reason: &str) -> String {
+    format!("לא ניתן לחשב את סטטוס התואר מול הקטלוג {catalog_name}, מכיוון שהוא פגום ({reason}). הבעיה דווחה לצוות האתר")
+}
+
 pub fn cannot_find_course() -> String {
     "שגיאה - קורס לא נמצא".to_string()
 }
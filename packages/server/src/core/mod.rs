@@ -1,10 +1,16 @@
 pub mod bank_rule;
+pub mod catalog_changes;
+pub mod catalog_diff;
 pub mod credit_transfer_graph;
 pub mod degree_status;
+pub mod faculty_rules;
+pub mod locale;
 pub mod messages;
 pub mod parser;
 pub mod types;
 
 pub mod catalog_validations;
 #[cfg(test)]
+pub mod regression;
+#[cfg(test)]
 pub mod tests;
@@ -3,7 +3,10 @@ use regex::Regex;
 
 use crate::{
     error::AppError,
-    resources::course::{Course, CourseStatus, Grade},
+    resources::{
+        catalog::RepetitionPolicy,
+        course::{Attempt, Course, CourseId, CourseStatus, Grade},
+    },
 };
 use std::collections::HashMap;
 
@@ -49,8 +52,8 @@ pub fn parse_copy_paste_data(data: &str) -> Result<Vec<CourseStatus>, AppError>
         return Err(AppError::Parser("Invalid copy paste data".into()));
     }
 
-    let mut courses = HashMap::<String, CourseStatus>::new();
-    let mut asterisk_courses = Vec::<CourseStatus>::new();
+    let mut courses = Vec::<CourseStatus>::new();
+    let mut attempts = HashMap::<CourseId, Vec<Attempt>>::new();
     let mut sport_courses = Vec::<CourseStatus>::new();
     let mut semester = String::new();
     let mut semester_counter: f32 = 0.0;
@@ -109,27 +112,41 @@ pub fn parse_copy_paste_data(data: &str) -> Result<Vec<CourseStatus>, AppError>
         };
         course_status.set_state();
         if course_status.course.id.starts_with("394") {
+            course_status.attempts.push(Attempt {
+                semester: course_status.semester.clone(),
+                grade: course_status.grade,
+            });
             sport_courses.push(course_status);
             continue;
         }
-        if line.contains('*') {
-            // If a student decides to retake a course for which he already had a grade,
-            // and then ends up not receiving a grade (לא השלים) for that course,
-            // The previous grade for this course is the valid one.
-            // Nevertheless, the previous grade will appear with an asterisk (*) in the grades pdf.
-            // Thus, to make sure we don't ignore these cases, we have to save a list of every asterisk-marked course,
-            // and then search this list for courses who fall in this particular case, and fix their grade.
-            asterisk_courses.push(course_status);
-        } else {
-            *courses
-                .entry(course_status.course.id.clone())
-                .or_insert(course_status) = course_status.clone();
+        attempts
+            .entry(course_status.course.id.clone())
+            .or_default()
+            .push(Attempt {
+                semester: course_status.semester.clone(),
+                grade: course_status.grade,
+            });
+        // A course marked with an asterisk (*) is a previous attempt which was replaced by a later one.
+        // Such attempts are kept in the attempt history of the course, and the repetition policy decides which attempt counts.
+        if !line.contains('*') {
+            // Courses are kept in the order of the attempt which appears last in the grade sheet
+            courses.retain(|cs| cs.course.id != course_status.course.id);
+            courses.push(course_status);
         }
     }
-    let mut vec_courses = courses.into_values().collect::<Vec<_>>();
+    let mut vec_courses = courses
+        .into_iter()
+        .map(|mut course_status| {
+            course_status.attempts = attempts
+                .remove(&course_status.course.id)
+                .unwrap_or_default();
+            course_status
+        })
+        .collect::<Vec<_>>();
 
-    // Fix the grades for said courses
-    set_grades_for_uncompleted_courses(&mut vec_courses, &asterisk_courses);
+    // The catalog is unknown at this point, so the default policy is applied.
+    // The catalog's policy is applied again when the degree status is computed.
+    apply_repetition_policy(&mut vec_courses, &RepetitionPolicy::default());
 
     vec_courses.append(&mut sport_courses);
 
@@ -137,49 +154,14 @@ pub fn parse_copy_paste_data(data: &str) -> Result<Vec<CourseStatus>, AppError>
         return Err(AppError::Parser("Invalid copy paste data".into()));
     }
 
-    for course_status in vec_courses.iter_mut() {
-        // Use asterisk courses to look for repetitions of the current course
-        let mut course_repetitions = asterisk_courses
-            .iter()
-            .filter(|cs| course_status.course.id == cs.course.id)
-            .collect::<Vec<_>>();
-
-        // Deduplicate the list of repetitions by semester
-        course_repetitions.dedup_by_key(|cs| cs.semester.clone());
-
-        // Remove the current course status from the list of repetitions
-        course_repetitions.retain(|cs| cs.semester != course_status.semester);
-
-        // Set the number of repetitions
-        course_status.times_repeated = course_repetitions.len();
-    }
-
     Ok(vec_courses)
 }
 
-fn set_grades_for_uncompleted_courses(
-    courses: &mut [CourseStatus],
-    asterisk_courses: &[CourseStatus],
-) {
-    // The candidate course statuses are those with uncompleted (לא השלים) grades.
-    // For each uncompleted course status, we iterate the asterisk list in reverse to find
-    // the closest (most chronologically advanced) course status with a grade (anything other than NotComplete (לא השלים)).
-    // If we find a course with such grade, then this course status will replace the old one.
-    let uncompleted_courses = courses
-        .iter_mut()
-        .filter(|c| c.grade == Some(Grade::NotComplete))
-        .collect::<Vec<_>>();
-    for uncompleted_course in uncompleted_courses {
-        for asterisk_course in asterisk_courses.iter().rev() {
-            if let Some(grade) = &asterisk_course.grade {
-                if uncompleted_course.course.id == asterisk_course.course.id
-                    && grade != &Grade::NotComplete
-                {
-                    *uncompleted_course = asterisk_course.clone();
-                    break;
-                }
-            }
-        }
+// Post-parse stage: sets the grade, semester and number of repetitions for every course
+// according to the attempts which count by the repetition policy.
+pub fn apply_repetition_policy(course_statuses: &mut [CourseStatus], policy: &RepetitionPolicy) {
+    for course_status in course_statuses.iter_mut() {
+        course_status.apply_repetition_policy(policy);
     }
 }
 
@@ -0,0 +1,145 @@
+// An offline regression harness for the degree status computation, which doesn't need a database.
+// A corpus is a directory of JSON fixtures:
+//   courses.json             the courses (as in the Courses collection) which are referenced by the corpus
+//   catalogs/<name>.json     the catalogs
+//   transcripts/<case>.json  anonymized transcripts: the course statuses of a student and the catalog to compute against
+//   expected/<case>.json     the expected degree status of each transcript (golden files)
+// Running with UPDATE_GOLDEN=1 rewrites the golden files with the current results.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+use crate::core::degree_status::{CorruptedCatalog, DegreeStatus};
+use crate::resources::{
+    catalog::Catalog,
+    course::{self, Course, CourseStatus},
+};
+
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+#[derive(Deserialize)]
+pub struct Transcript {
+    pub catalog: String, // The name of the catalog file, without its extension
+    pub course_statuses: Vec<CourseStatus>,
+}
+
+pub struct CaseResult {
+    pub name: String,
+    pub diff: Vec<String>, // Empty if the degree status matches its golden file
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// Returns the names (without the extension) and paths of the JSON files in the directory, sorted by name
+fn json_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some((name, path))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+// Computes the degree status the same way the server does, with the tags of the courses filled in
+pub fn compute(
+    course_statuses: Vec<CourseStatus>,
+    catalog: Catalog,
+    courses: &[Course],
+) -> Result<DegreeStatus, CorruptedCatalog> {
+    let mut degree_status = DegreeStatus {
+        course_statuses,
+        ..Default::default()
+    };
+    degree_status.fill_tags(courses);
+    degree_status.compute(catalog, course::vec_to_map(courses.to_vec()))?;
+    Ok(degree_status)
+}
+
+// Lists the differences between the expected and the actual JSON values, one line per differing path
+pub fn json_diff(path: &str, expected: &Value, actual: &Value, diff: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys = expected.keys().chain(actual.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let key_path = format!("{path}.{key}");
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => json_diff(&key_path, expected, actual, diff),
+                    (Some(expected), None) => diff.push(format!("- {key_path}: {expected}")),
+                    (None, Some(actual)) => diff.push(format!("+ {key_path}: {actual}")),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for index in 0..expected.len().max(actual.len()) {
+                let index_path = format!("{path}[{index}]");
+                match (expected.get(index), actual.get(index)) {
+                    (Some(expected), Some(actual)) => {
+                        json_diff(&index_path, expected, actual, diff)
+                    }
+                    (Some(expected), None) => diff.push(format!("- {index_path}: {expected}")),
+                    (None, Some(actual)) => diff.push(format!("+ {index_path}: {actual}")),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if expected != actual => diff.push(format!("~ {path}: {expected} -> {actual}")),
+        _ => {}
+    }
+}
+
+// Computes the degree status of every transcript in the corpus and compares it with its golden file.
+// Golden files which are missing are reported as a difference, unless they are being updated.
+pub fn run_corpus(dir: &Path) -> Result<Vec<CaseResult>, String> {
+    let update_golden = std::env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| value == "1");
+    let courses = read_json::<Vec<Course>>(&dir.join("courses.json"))?;
+    let catalogs = json_files(&dir.join("catalogs"))?
+        .into_iter()
+        .map(|(name, path)| Ok((name, read_json::<Catalog>(&path)?)))
+        .collect::<Result<HashMap<_, _>, String>>()?;
+    let expected_dir = dir.join("expected");
+
+    let mut results = Vec::new();
+    for (name, path) in json_files(&dir.join("transcripts"))? {
+        let transcript = read_json::<Transcript>(&path)?;
+        let catalog = catalogs
+            .get(&transcript.catalog)
+            .ok_or_else(|| format!("{}: unknown catalog {}", path.display(), transcript.catalog))?
+            .clone();
+        let degree_status = compute(transcript.course_statuses, catalog, &courses)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        let actual = serde_json::to_value(&degree_status).map_err(|e| e.to_string())?;
+
+        let expected_path = expected_dir.join(format!("{name}.json"));
+        let mut diff = Vec::new();
+        if update_golden {
+            let content = serde_json::to_string_pretty(&actual).map_err(|e| e.to_string())?;
+            fs::create_dir_all(&expected_dir).map_err(|e| e.to_string())?;
+            fs::write(&expected_path, content + "\n")
+                .map_err(|e| format!("{}: {e}", expected_path.display()))?;
+        } else if expected_path.exists() {
+            json_diff("", &read_json::<Value>(&expected_path)?, &actual, &mut diff);
+        } else {
+            diff.push(format!("missing golden file {}", expected_path.display()));
+        }
+        results.push(CaseResult { name, diff });
+    }
+    Ok(results)
+}
@@ -1,17 +1,30 @@
 use crate::consts;
 use crate::core::bank_rule::BankRuleHandler;
-use crate::core::catalog_validations::validate_catalog;
+use crate::core::catalog_changes::CatalogChanges;
+use crate::core::catalog_diff::{self, BankChange, CourseMove};
+use crate::core::catalog_validations::{
+    catalog_report, validate_catalog, validate_computable_catalog, Severity,
+};
+use crate::core::credit_transfer_graph::{CreditTransferError, CreditTransferGraph, Transfers};
 use crate::core::degree_status::DegreeStatus;
+use crate::core::faculty_rules::english::ENGLISH_REQUIREMENT_NAME;
+use crate::core::locale::Language;
 use crate::core::parser;
-use crate::core::types::CreditOverflow;
+use crate::core::types::Rule;
+use crate::core::types::{CreditOverflow, SpecializationGroup, SpecializationGroups};
 use crate::db::Db;
-use crate::resources::catalog::Catalog;
+use crate::resources::catalog::{
+    Catalog, CountedAttempt, CourseSharing, EnglishRequirement, ExemptionPolicy, Faculty,
+    MedicinePolicy, RepetitionPolicy,
+};
 use crate::resources::course::CourseState::NotComplete;
 use crate::resources::course::Grade::Numeric;
-use crate::resources::course::{self, Course, CourseState, CourseStatus, Grade, Tag};
+use crate::resources::course::{
+    self, Attempt, Course, CourseBank, CourseState, CourseStatus, Grade, Tag,
+};
 use actix_rt::test;
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
 use super::types::Requirement;
@@ -144,6 +157,119 @@ async fn test_parser_course_status_repetitions() {
     dbg!(course_status3);
 }
 
+#[test]
+async fn test_repetition_policy() {
+    let mut course_status = CourseStatus {
+        attempts: vec![
+            Attempt {
+                semester: Some("חורף_1".to_string()),
+                grade: Some(Grade::Numeric(40)),
+            },
+            Attempt {
+                semester: Some("אביב_2".to_string()),
+                grade: Some(Grade::Numeric(90)),
+            },
+            Attempt {
+                semester: Some("חורף_3".to_string()),
+                grade: Some(Grade::Numeric(70)),
+            },
+            Attempt {
+                semester: Some("אביב_4".to_string()),
+                grade: Some(Grade::NotComplete),
+            },
+        ],
+        ..Default::default()
+    };
+
+    // The last attempt was not completed, so the most recent graded attempt counts
+    let mut policy = RepetitionPolicy::default();
+    parser::apply_repetition_policy(std::slice::from_mut(&mut course_status), &policy);
+    assert_eq!(course_status.grade, Some(Grade::Numeric(70)));
+    assert_eq!(course_status.semester, Some("חורף_3".to_string()));
+    assert_eq!(course_status.state, Some(CourseState::Complete));
+    assert_eq!(course_status.times_repeated, 3);
+    assert_eq!(course_status.grades_for_average(&policy), vec![70]);
+
+    policy.counted_attempt = CountedAttempt::Best;
+    parser::apply_repetition_policy(std::slice::from_mut(&mut course_status), &policy);
+    assert_eq!(course_status.grade, Some(Grade::Numeric(90)));
+    assert_eq!(course_status.semester, Some("אביב_2".to_string()));
+    assert_eq!(course_status.times_repeated, 3);
+
+    policy.failed_attempts_in_average = true;
+    assert_eq!(course_status.grades_for_average(&policy), vec![90, 40]);
+
+    // The student retakes a course which wasn't passed yet, so the course is in progress
+    course_status.attempts = vec![
+        Attempt {
+            semester: Some("חורף_1".to_string()),
+            grade: Some(Grade::Numeric(40)),
+        },
+        Attempt {
+            semester: Some("אביב_2".to_string()),
+            grade: None,
+        },
+    ];
+    parser::apply_repetition_policy(std::slice::from_mut(&mut course_status), &policy);
+    assert_eq!(course_status.grade, None);
+    assert_eq!(course_status.state, Some(CourseState::InProgress));
+    assert_eq!(course_status.times_repeated, 1);
+}
+
+#[test]
+async fn test_best_attempt_with_mixed_grades() {
+    let attempt = |semester: &str, grade: Grade| Attempt {
+        semester: Some(semester.to_string()),
+        grade: Some(grade),
+    };
+    let policy = RepetitionPolicy {
+        counted_attempt: CountedAttempt::Best,
+        ..Default::default()
+    };
+    let best_grade = |attempts: Vec<Attempt>| {
+        let mut course_status = CourseStatus {
+            attempts,
+            ..Default::default()
+        };
+        parser::apply_repetition_policy(std::slice::from_mut(&mut course_status), &policy);
+        course_status.grade
+    };
+
+    // A passed numeric grade is preferred over a later pass without a grade
+    assert_eq!(
+        best_grade(vec![
+            attempt("חורף_1", Grade::Numeric(85)),
+            attempt("אביב_2", Grade::Binary(true)),
+        ]),
+        Some(Grade::Numeric(85))
+    );
+    // A passed attempt is preferred over a failed numeric grade
+    assert_eq!(
+        best_grade(vec![
+            attempt("חורף_1", Grade::Numeric(50)),
+            attempt("אביב_2", Grade::ExemptionWithCredit),
+            attempt("חורף_3", Grade::Binary(false)),
+        ]),
+        Some(Grade::ExemptionWithCredit)
+    );
+    // Among failed attempts, the numeric grade is preferred
+    assert_eq!(
+        best_grade(vec![
+            attempt("חורף_1", Grade::Numeric(40)),
+            attempt("אביב_2", Grade::Binary(false)),
+        ]),
+        Some(Grade::Numeric(40))
+    );
+    // Grades which aren't numeric are equal, so the latest attempt wins
+    assert_eq!(
+        best_grade(vec![
+            attempt("חורף_1", Grade::ExemptionWithCredit),
+            attempt("אביב_2", Grade::Binary(true)),
+        ]),
+        Some(Grade::Binary(true))
+    );
+}
+
 lazy_static! {
     static ref COURSES: HashMap<String, Course> = HashMap::from([
         (
@@ -240,8 +366,8 @@ macro_rules! create_bank_rule_handler {
             courses: &COURSES,
             credit_overflow: $credit_overflow,
             courses_overflow: $courses_overflow,
-            catalog_replacements: &HashMap::new(),
-            common_replacements: &HashMap::new(),
+            catalog_replacements: &std::collections::BTreeMap::new(),
+            common_replacements: &std::collections::BTreeMap::new(),
         }
     };
 }
@@ -341,6 +467,7 @@ pub fn create_degree_status() -> DegreeStatus {
         course_bank_requirements: Vec::<Requirement>::new(),
         overflow_msgs: Vec::<String>::new(),
         total_credit: 0.0,
+        ..Default::default()
     }
 }
 
@@ -528,6 +655,741 @@ async fn test_duplicated_courses() {
     }
 }
 
+// A catalog with a mandatory bank and a bank which collects its overflow, for the courses of create_degree_status
+fn create_catalog() -> Catalog {
+    Catalog {
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: Some(8.5),
+            },
+            CourseBank {
+                name: "reshima".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(3.0),
+            },
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "hova".to_string(),
+            to: "reshima".to_string(),
+            ..Default::default()
+        }],
+        course_to_bank: BTreeMap::from([
+            ("104031".to_string(), "hova".to_string()),
+            ("236303".to_string(), "hova".to_string()),
+        ]),
+        ..Default::default()
+    }
+}
+
+// The same catalog for the medicine faculty, whose mandatory bank is checked by the preclinical rules
+fn create_medicine_catalog(medicine_policy: MedicinePolicy) -> Catalog {
+    let mut catalog = Catalog {
+        faculty: Faculty::Medicine,
+        medicine_policy,
+        ..create_catalog()
+    };
+    catalog.course_banks[0].name = consts::medicine::ALL_BANK_NAME.to_string();
+    catalog.credit_overflows[0].from = consts::medicine::ALL_BANK_NAME.to_string();
+    for bank_name in catalog.course_to_bank.values_mut() {
+        *bank_name = consts::medicine::ALL_BANK_NAME.to_string();
+    }
+    catalog
+}
+
+fn create_exemption_catalog(exemption_policy: ExemptionPolicy) -> Catalog {
+    Catalog {
+        exemption_policy,
+        ..create_catalog()
+    }
+}
+
+fn create_exemption_degree_status() -> DegreeStatus {
+    let mut degree_status = create_degree_status();
+    // The grade sheet doesn't include the credit of courses with exemption without credit
+    degree_status.course_statuses[4].course.credit = 0.0;
+    degree_status.course_statuses[4].grade = Some(Grade::ExemptionWithoutCredit);
+    degree_status
+}
+
+#[test]
+async fn test_exemption_without_credit() {
+    let mut degree_status = create_exemption_degree_status();
+    degree_status
+        .compute(
+            create_exemption_catalog(ExemptionPolicy::TransferCredit),
+            COURSES.clone(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        degree_status.course_bank_requirements[0].credit_requirement,
+        Some(5.5)
+    );
+    assert!(degree_status.course_bank_requirements[0].completed);
+    // The credit of the exempted course is added to the requirement of the next bank
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_requirement,
+        Some(6.0)
+    );
+    assert!(degree_status
+        .overflow_msgs
+        .contains(&messages::exemption_transferred_credit_msg(3.0, "hova", "reshima").to_string()));
+
+    let mut degree_status = create_exemption_degree_status();
+    degree_status
+        .compute(
+            create_exemption_catalog(ExemptionPolicy::ReduceCredit),
+            COURSES.clone(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        degree_status.course_bank_requirements[0].credit_requirement,
+        Some(5.5)
+    );
+    // The credit of the exempted course is not added to the next bank
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_requirement,
+        Some(3.0)
+    );
+    assert!(degree_status
+        .overflow_msgs
+        .contains(&messages::exemption_reduced_credit_msg(3.0, "hova").to_string()));
+    assert_eq!(degree_status.summary.reduced_credit, 3.0);
+
+    // A user who completed all the banks completed the degree, whose requirement was reduced by the exemption
+    let mut catalog = create_exemption_catalog(ExemptionPolicy::ReduceCredit);
+    catalog.total_credit = 11.5;
+    catalog
+        .course_to_bank
+        .insert("114052".to_string(), "reshima".to_string());
+    let mut degree_status = create_exemption_degree_status();
+    degree_status.compute(catalog, COURSES.clone()).unwrap();
+    assert_eq!(degree_status.summary.remaining_credit, 0.0);
+    assert!(degree_status.summary.completed);
+}
+
+#[test]
+async fn test_capped_credit_overflow() {
+    let create_catalog = |credit_overflow: CreditOverflow| Catalog {
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: Some(3.0),
+            },
+            CourseBank {
+                name: "reshima".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(3.0),
+            },
+        ],
+        credit_overflows: vec![credit_overflow],
+        course_to_bank: BTreeMap::from([("104031".to_string(), "hova".to_string())]),
+        ..Default::default()
+    };
+
+    // 2.5 credit overflow from hova, but only 1 credit may be transferred to reshima
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(
+            create_catalog(CreditOverflow {
+                from: "hova".to_string(),
+                to: "reshima".to_string(),
+                cap: Some(1.0),
+                ..Default::default()
+            }),
+            COURSES.clone(),
+        )
+        .unwrap();
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_completed,
+        1.0
+    );
+    assert!(degree_status
+        .overflow_msgs
+        .contains(&messages::credit_overflow_msg(1.0, "hova", "reshima").to_string()));
+    assert!(degree_status.overflow_msgs.contains(
+        &messages::discarded_credit_overflow_msg(1.5, 1.0, "hova", "reshima").to_string()
+    ));
+
+    // Each credit point which is transferred from hova counts as half a point in reshima
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(
+            create_catalog(CreditOverflow {
+                from: "hova".to_string(),
+                to: "reshima".to_string(),
+                ratio: Some(0.5),
+                ..Default::default()
+            }),
+            COURSES.clone(),
+        )
+        .unwrap();
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_completed,
+        1.25
+    );
+    assert!(degree_status.overflow_msgs.contains(
+        &messages::converted_credit_overflow_msg(2.5, 1.25, "hova", "reshima").to_string()
+    ));
+}
+
+#[test]
+async fn test_prioritized_credit_overflow() {
+    let mut catalog = Catalog {
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: Some(3.0),
+            },
+            CourseBank {
+                name: "bechira".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(5.0),
+            },
+            CourseBank {
+                name: "reshima".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(4.0),
+            },
+        ],
+        // The excess credit of hova fills reshima first, and the rest spills to bechira
+        credit_overflows: vec![
+            CreditOverflow {
+                from: "hova".to_string(),
+                to: "reshima".to_string(),
+                ..Default::default()
+            },
+            CreditOverflow {
+                from: "hova".to_string(),
+                to: "bechira".to_string(),
+                ..Default::default()
+            },
+        ],
+        course_to_bank: BTreeMap::from([
+            ("104031".to_string(), "hova".to_string()),
+            ("114052".to_string(), "reshima".to_string()),
+        ]),
+        ..Default::default()
+    };
+    assert!(validate_catalog(&catalog).is_ok());
+
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+
+    let reshima = &degree_status.course_bank_requirements[1];
+    assert_eq!(reshima.course_bank_name, "reshima");
+    assert_eq!(reshima.credit_completed, 4.0);
+    assert!(reshima.completed);
+    let bechira = &degree_status.course_bank_requirements[2];
+    assert_eq!(bechira.course_bank_name, "bechira");
+    assert_eq!(bechira.credit_completed, 2.0);
+    assert_eq!(
+        degree_status.overflow_msgs[..2],
+        [
+            messages::credit_overflow_msg(0.5, "hova", "reshima").to_string(),
+            messages::credit_overflow_msg(2.0, "hova", "bechira").to_string(),
+        ]
+    );
+
+    let compute = |catalog: &Catalog| {
+        let mut degree_status = create_degree_status();
+        degree_status
+            .compute(catalog.clone(), COURSES.clone())
+            .unwrap();
+        degree_status
+    };
+
+    // reshima is already full, so all the excess credit of hova goes to bechira
+    let mut full_catalog = catalog.clone();
+    full_catalog.course_banks[2].credit = Some(3.5);
+    let degree_status = compute(&full_catalog);
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_completed,
+        3.5
+    );
+    assert_eq!(
+        degree_status.course_bank_requirements[2].credit_completed,
+        2.5
+    );
+    assert_eq!(
+        degree_status.overflow_msgs[0],
+        messages::credit_overflow_msg(2.5, "hova", "bechira").to_string()
+    );
+
+    // The cap of the transfer to reshima is reached before reshima is full, so the rest is discarded
+    let mut capped_catalog = catalog.clone();
+    capped_catalog.credit_overflows[0].cap = Some(0.25);
+    let degree_status = compute(&capped_catalog);
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_completed,
+        3.75
+    );
+    assert_eq!(
+        degree_status.course_bank_requirements[2].credit_completed,
+        0.0
+    );
+    assert_eq!(
+        degree_status.overflow_msgs[..2],
+        [
+            messages::credit_overflow_msg(0.25, "hova", "reshima").to_string(),
+            messages::discarded_credit_overflow_msg(2.25, 0.25, "hova", "reshima").to_string(),
+        ]
+    );
+
+    // reshima is full before the cap is reached, so the rest still goes to bechira
+    capped_catalog.credit_overflows[0].cap = Some(1.0);
+    let degree_status = compute(&capped_catalog);
+    assert_eq!(
+        degree_status.course_bank_requirements[1].credit_completed,
+        4.0
+    );
+    assert_eq!(
+        degree_status.course_bank_requirements[2].credit_completed,
+        2.0
+    );
+    assert!(!degree_status
+        .typed_overflow_msgs
+        .iter()
+        .any(|msg| matches!(msg, messages::Message::DiscardedCreditOverflow { .. })));
+
+    // The credit of a bank without a credit requirement is counted in its targets by priority
+    let mut no_credit_catalog = catalog.clone();
+    no_credit_catalog.course_banks[0].credit = None;
+    let degree_status = compute(&no_credit_catalog);
+    assert_eq!(
+        degree_status.overflow_msgs[..2],
+        [
+            messages::credit_overflow_detailed_msg("hova", "reshima").to_string(),
+            messages::credit_overflow_detailed_msg("hova", "bechira").to_string(),
+        ]
+    );
+
+    // bechira comes after reshima, so a transfer from bechira to reshima closes a cycle
+    catalog.credit_overflows.push(CreditOverflow {
+        from: "bechira".to_string(),
+        to: "reshima".to_string(),
+        ..Default::default()
+    });
+    assert!(validate_catalog(&catalog).is_err());
+}
+
+#[test]
+async fn test_degree_summary() {
+    let mut catalog = create_catalog();
+    catalog.course_banks.truncate(1);
+    catalog.credit_overflows.clear();
+    catalog.total_credit = 20.0;
+
+    let mut degree_status = create_degree_status();
+    degree_status.course_statuses[0].semester = Some("חורף_1".to_string());
+    degree_status.course_statuses[4].semester = Some("אביב_2".to_string());
+    degree_status.course_statuses[7].semester = Some("קיץ_2.5".to_string());
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+
+    assert!(!degree_status.summary.completed);
+    assert!(degree_status.summary.incomplete_banks.is_empty());
+    assert_eq!(degree_status.summary.remaining_credit, 11.5);
+    // 9.5 credit in 2 semesters (the summer semester is not counted)
+    assert_eq!(degree_status.summary.estimated_semesters_left, Some(3));
+
+    // The user didn't complete a course in hova
+    let course_status = degree_status.get_mut_course_status("236303").unwrap();
+    course_status.grade = Some(Grade::Numeric(40));
+    course_status.state = Some(CourseState::NotComplete);
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+
+    assert_eq!(degree_status.summary.incomplete_banks, vec!["hova"]);
+    assert_eq!(degree_status.summary.remaining_credit, 14.5);
+
+    catalog.total_credit = 8.5;
+    let course_status = degree_status.get_mut_course_status("236303").unwrap();
+    course_status.grade = Some(Grade::Numeric(90));
+    course_status.state = Some(CourseState::Complete);
+    degree_status.compute(catalog, COURSES.clone()).unwrap();
+
+    assert!(degree_status.summary.completed);
+    assert_eq!(degree_status.summary.remaining_credit, 0.0);
+    assert_eq!(degree_status.summary.estimated_semesters_left, Some(0));
+}
+
+#[test]
+async fn test_secondary_catalogs() {
+    let mut catalog = create_catalog();
+    catalog.course_banks.truncate(1);
+    catalog.credit_overflows.clear();
+    let secondary_catalog = Catalog {
+        name: "minor".to_string(),
+        course_banks: vec![CourseBank {
+            name: "minor_hova".to_string(),
+            rule: Rule::All,
+            credit: Some(9.0),
+        }],
+        course_to_bank: BTreeMap::from([
+            ("104031".to_string(), "minor_hova".to_string()),
+            ("114052".to_string(), "minor_hova".to_string()),
+        ]),
+        ..Default::default()
+    };
+
+    // By default a course counts towards one catalog only
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute_with_secondary_catalogs(
+            catalog.clone(),
+            vec![(secondary_catalog.clone(), CourseSharing::None)],
+            COURSES.clone(),
+        )
+        .unwrap();
+    assert!(degree_status.course_bank_requirements[0].completed);
+    let secondary_status = &degree_status.secondary_statuses[0].degree_status;
+    assert_eq!(degree_status.secondary_statuses[0].catalog_name, "minor");
+    assert!(!secondary_status.course_bank_requirements[0].completed);
+    assert!(!secondary_status
+        .get_course_status("104031")
+        .is_some_and(|course_status| course_status.completed()));
+    assert_eq!(degree_status.shared_courses.len(), 1);
+    assert_eq!(degree_status.shared_courses[0].course_id, "104031");
+    assert_eq!(degree_status.shared_courses[0].primary_bank, "hova");
+    assert_eq!(degree_status.shared_courses[0].secondary_bank, None);
+
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute_with_secondary_catalogs(
+            catalog,
+            vec![(
+                secondary_catalog,
+                CourseSharing::Courses(vec!["104031".to_string()]),
+            )],
+            COURSES.clone(),
+        )
+        .unwrap();
+    let secondary_status = &degree_status.secondary_statuses[0].degree_status;
+    assert!(secondary_status.course_bank_requirements[0].completed);
+    assert_eq!(
+        secondary_status
+            .get_course_status("104031")
+            .and_then(|course_status| course_status.r#type.clone()),
+        Some("minor_hova".to_string())
+    );
+    assert_eq!(degree_status.shared_courses.len(), 1);
+    assert_eq!(
+        degree_status.shared_courses[0].secondary_bank,
+        Some("minor_hova".to_string())
+    );
+}
+
+#[test]
+async fn test_secondary_catalogs_with_courses_in_progress() {
+    let catalog = create_catalog();
+    let secondary_catalog = Catalog {
+        name: "minor".to_string(),
+        course_banks: vec![CourseBank {
+            name: "minor_hova".to_string(),
+            rule: Rule::All,
+            credit: Some(3.5),
+        }],
+        course_to_bank: BTreeMap::from([("114052".to_string(), "minor_hova".to_string())]),
+        ..Default::default()
+    };
+    let mut degree_status = create_degree_status();
+    let course_status = degree_status.get_mut_course_status("114052").unwrap();
+    course_status.state = Some(CourseState::InProgress);
+    course_status.grade = None;
+
+    // The same flow as computing the degree status with "compute_in_progress"
+    let course_list = degree_status.set_in_progress_to_complete();
+    degree_status
+        .compute_with_secondary_catalogs(
+            catalog,
+            vec![(secondary_catalog, CourseSharing::None)],
+            COURSES.clone(),
+        )
+        .unwrap();
+    degree_status.set_to_in_progress(course_list);
+
+    let secondary_status = &degree_status.secondary_statuses[0].degree_status;
+    // The course in progress is counted by the secondary catalog, but it is still shown as in progress
+    assert!(secondary_status.course_bank_requirements[0].completed);
+    assert_eq!(
+        secondary_status.get_course_status("114052").unwrap().state,
+        Some(CourseState::InProgress)
+    );
+    assert_eq!(
+        degree_status.get_course_status("114052").unwrap().state,
+        Some(CourseState::InProgress)
+    );
+}
+
+#[test]
+async fn test_compare_catalogs() {
+    let create_catalog = |name: &str, course_ids: &[&str]| Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: name.to_string(),
+        course_banks: vec![CourseBank {
+            name: "hova".to_string(),
+            rule: Rule::All,
+            credit: None,
+        }],
+        course_to_bank: course_ids
+            .iter()
+            .map(|id| (id.to_string(), "hova".to_string()))
+            .collect(),
+        total_credit: 10.0,
+        ..Default::default()
+    };
+    let old_catalog = create_catalog("old", &["104031", "104166"]);
+    let new_catalog = create_catalog("new", &["104031", "114052"]);
+
+    let mut degree_status = create_degree_status();
+    degree_status.course_statuses[0].r#type = Some("reshima".to_string());
+    let comparison = degree_status
+        .compare_catalogs(vec![old_catalog, new_catalog.clone()], COURSES.clone())
+        .unwrap();
+
+    assert_eq!(comparison.statuses.len(), 2);
+    assert_eq!(comparison.statuses[0].catalog_name, "old");
+    assert_eq!(
+        comparison.statuses[0]
+            .degree_status
+            .summary
+            .incomplete_banks,
+        vec!["hova"]
+    );
+    assert!(
+        comparison.statuses[1]
+            .degree_status
+            .course_bank_requirements[0]
+            .completed
+    );
+    assert_eq!(comparison.closest_catalog, Some(new_catalog.id));
+    // The user's own status is not changed by the comparison
+    assert_eq!(
+        degree_status.course_statuses[0].r#type,
+        Some("reshima".to_string())
+    );
+}
+
+#[test]
+async fn test_degree_status_diff() {
+    let mut catalog = create_catalog();
+    catalog.course_banks.truncate(1);
+    catalog.credit_overflows.clear();
+
+    let mut before = create_degree_status();
+    let input_hash = before.input_hash(&catalog.id);
+    before.compute(catalog.clone(), COURSES.clone()).unwrap();
+    assert_eq!(create_degree_status().input_hash(&catalog.id), input_hash);
+
+    // Only the inputs of the computation are hashed
+    let mut other = create_degree_status();
+    other.course_statuses[0].additional_msg = Some("message".to_string());
+    other.course_statuses[0].times_repeated = 2;
+    assert_eq!(other.input_hash(&catalog.id), input_hash);
+    other.course_statuses[0].grade = Some(Numeric(90));
+    assert_ne!(other.input_hash(&catalog.id), input_hash);
+
+    // An owner moves a course from one bank to another
+    catalog.course_banks.push(CourseBank {
+        name: "reshima".to_string(),
+        rule: Rule::AccumulateCredit,
+        credit: Some(3.0),
+    });
+    catalog
+        .course_to_bank
+        .insert("236303".to_string(), "reshima".to_string());
+    let mut after = create_degree_status();
+    after.compute(catalog, COURSES.clone()).unwrap();
+
+    let diff = before.diff(&after);
+    assert_eq!(
+        diff.requirements
+            .iter()
+            .map(|change| change.course_bank_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["hova", "reshima"]
+    );
+    assert!(diff.requirements[1].before.is_none());
+    assert_eq!(diff.course_assignments.len(), 1);
+    assert_eq!(diff.course_assignments[0].course_id, "236303");
+    assert_eq!(diff.course_assignments[0].before, Some("hova".to_string()));
+    assert_eq!(
+        diff.course_assignments[0].after,
+        Some("reshima".to_string())
+    );
+    assert!(before.diff(&before).requirements.is_empty());
+}
+
+#[test]
+async fn test_typed_messages() {
+    let msg = messages::credit_overflow_msg(1.0, "hova", "reshima");
+    assert_eq!(
+        serde_json::to_value(&msg).unwrap(),
+        serde_json::json!({
+            "code": "credit_overflow",
+            "params": {"credit": 1.0, "from": "hova", "to": "reshima"}
+        })
+    );
+    assert_eq!(msg.to_string(), "נקודה אחת עברה מhova לreshima");
+
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(
+            create_exemption_catalog(ExemptionPolicy::TransferCredit),
+            COURSES.clone(),
+        )
+        .unwrap();
+    // The rendered messages are kept for clients which display them as is
+    assert_eq!(
+        degree_status
+            .typed_overflow_msgs
+            .iter()
+            .map(|msg| msg.to_string())
+            .collect::<Vec<_>>(),
+        degree_status.overflow_msgs
+    );
+    assert!(degree_status
+        .typed_overflow_msgs
+        .contains(&messages::credit_leftovers_msg(0.0)));
+}
+
+#[test]
+async fn test_localized_degree_status() {
+    let mut degree_status = create_exemption_degree_status();
+    degree_status
+        .compute(
+            create_exemption_catalog(ExemptionPolicy::TransferCredit),
+            COURSES.clone(),
+        )
+        .unwrap();
+    let hebrew_msgs = degree_status.overflow_msgs.clone();
+
+    degree_status.localize(Language::English);
+    assert!(degree_status
+        .overflow_msgs
+        .contains(&"You have no excess credit".to_string()));
+    assert!(degree_status.overflow_msgs.contains(
+        &"Due to exemptions without credit in hova, 3 credit points were added to the requirement of reshima"
+            .to_string()
+    ));
+
+    degree_status.localize(Language::Hebrew);
+    assert_eq!(degree_status.overflow_msgs, hebrew_msgs);
+}
+
+#[test]
+async fn test_deterministic_computation() {
+    let from_pdf = std::fs::read_to_string("../docs/pdf_ctrl_c_ctrl_v.txt")
+        .expect("Something went wrong reading the file");
+    let course_statuses =
+        parser::parse_copy_paste_data(&from_pdf).expect("failed to parse pdf data");
+    let course_ids = course_statuses
+        .iter()
+        .map(|course_status| course_status.course.id.clone())
+        .collect::<Vec<_>>();
+    let courses = course_statuses
+        .iter()
+        .map(|course_status| {
+            (
+                course_status.course.id.clone(),
+                course_status.course.clone(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    // The specialization groups overlap, so the chosen groups depend on the iteration order
+    let groups_courses = course_ids[8..20].to_vec();
+    let catalog = Catalog {
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: None,
+            },
+            CourseBank {
+                name: "groups".to_string(),
+                rule: Rule::SpecializationGroups(SpecializationGroups {
+                    groups_list: (0..4)
+                        .map(|i| SpecializationGroup {
+                            name: format!("group_{}", i),
+                            courses_sum: 2,
+                            course_list: groups_courses[i * 2..i * 2 + 6].to_vec(),
+                            mandatory: None,
+                        })
+                        .collect(),
+                    groups_number: 2,
+                }),
+                credit: Some(10.0),
+            },
+            CourseBank {
+                name: "reshima".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(6.0),
+            },
+            CourseBank {
+                name: "bechira".to_string(),
+                rule: Rule::Elective,
+                credit: Some(4.0),
+            },
+        ],
+        credit_overflows: vec![
+            CreditOverflow {
+                from: "groups".to_string(),
+                to: "reshima".to_string(),
+                ..Default::default()
+            },
+            CreditOverflow {
+                from: "reshima".to_string(),
+                to: "bechira".to_string(),
+                ..Default::default()
+            },
+        ],
+        course_to_bank: course_ids[..8]
+            .iter()
+            .map(|id| (id.clone(), "hova".to_string()))
+            .chain(
+                groups_courses
+                    .iter()
+                    .map(|id| (id.clone(), "groups".to_string())),
+            )
+            .chain(
+                course_ids[20..30]
+                    .iter()
+                    .map(|id| (id.clone(), "reshima".to_string())),
+            )
+            .collect(),
+        total_credit: 120.0,
+        ..Default::default()
+    };
+
+    let run = || {
+        let mut degree_status = DegreeStatus {
+            course_statuses: parser::parse_copy_paste_data(&from_pdf)
+                .expect("failed to parse pdf data"),
+            ..Default::default()
+        };
+        degree_status
+            .compute(catalog.clone(), courses.clone())
+            .unwrap();
+        serde_json::to_string(&degree_status).expect("failed to serialize degree status")
+    };
+
+    let expected = run();
+    for _ in 0..20 {
+        assert_eq!(run(), expected);
+    }
+}
+
 // ------------------------------------------------------------------------------------------------------
 // Test core function in a full flow
 // ------------------------------------------------------------------------------------------------------
@@ -547,7 +1409,9 @@ async fn run_degree_status(mut degree_status: DegreeStatus, catalog: Catalog) ->
         .await
         .expect("failed to get all courses");
     degree_status.fill_tags(&vec_courses);
-    degree_status.compute(catalog, course::vec_to_map(vec_courses));
+    degree_status
+        .compute(catalog, course::vec_to_map(vec_courses))
+        .unwrap();
     degree_status
 }
 
@@ -672,19 +1536,19 @@ async fn test_missing_credit() {
 
     assert_eq!(
         degree_status.overflow_msgs[0],
-        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א")
+        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[1],
-        messages::missing_credit_msg(1.0, "חובה", "רשימה ב")
+        messages::missing_credit_msg(1.0, "חובה", "רשימה ב").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[2],
-        messages::credit_overflow_msg(6.0, "בחירת העשרה", "בחירה חופשית")
+        messages::credit_overflow_msg(6.0, "בחירת העשרה", "בחירה חופשית").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[3],
-        messages::credit_leftovers_msg(5.5)
+        messages::credit_leftovers_msg(5.5).to_string()
     );
 }
 
@@ -738,9 +1602,7 @@ async fn test_overflow_credit() {
     );
     assert_eq!(
         degree_status.course_bank_requirements[5].message,
-        Some(messages::completed_chain_msg(
-            vec!["פיסיקה 2פ'".to_string()]
-        ))
+        Some(messages::completed_chain_msg(vec!["פיסיקה 2פ'".to_string()]).to_string())
     );
 
     assert_eq!(
@@ -772,19 +1634,19 @@ async fn test_overflow_credit() {
 
     assert_eq!(
         degree_status.overflow_msgs[0],
-        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א")
+        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[1],
-        messages::credit_overflow_msg(1.5, "חובה", "רשימה ב")
+        messages::credit_overflow_msg(1.5, "חובה", "רשימה ב").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[2],
-        messages::credit_overflow_msg(0.5, "שרשרת מדעית", "רשימה ב")
+        messages::credit_overflow_msg(0.5, "שרשרת מדעית", "רשימה ב").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[3],
-        messages::credit_leftovers_msg(0.0)
+        messages::credit_leftovers_msg(0.0).to_string()
     );
 }
 
@@ -806,7 +1668,8 @@ async fn test_postprocessing_english_requirement() {
     // The student has english exemption, so he has to complete to english content courses
     assert_eq!(
         degree_status.overflow_msgs[4],
-        messages::english_requirement_for_exempt_students_msg()
+        messages::english_requirement_for_exempt_students_msg(consts::EXEMPT_COURSES_COUNT_DEMAND)
+            .to_string()
     );
 
     // Update technical english advanced b course grade to numeric, thus the student did not get exemption
@@ -825,7 +1688,10 @@ async fn test_postprocessing_english_requirement() {
 
     assert_eq!(
         degree_status.overflow_msgs[4],
-        messages::english_requirement_for_technical_advanced_b_students_msg()
+        messages::english_requirement_for_technical_advanced_b_students_msg(
+            consts::ADVANCED_B_COURSES_COUNT_DEMAND
+        )
+        .to_string()
     );
 
     // Update technical english advanced b course grade to fail, thus the a message shouldn't be displayed for the user
@@ -862,17 +1728,22 @@ async fn test_postprocessing_medicine_requirement() {
 
     // The student repeated a mandatory course 274109 twice
     assert!(
-        degree_status
-            .overflow_msgs
-            .contains(&messages::medicine_preclinical_course_repetitions_error_msg(vec![cs1, cs2]))
-            || degree_status.overflow_msgs.contains(
-                &messages::medicine_preclinical_course_repetitions_error_msg(vec![cs2, cs1])
-            )
+        degree_status.overflow_msgs.contains(
+            &messages::medicine_preclinical_course_repetitions_error_msg(vec![cs1, cs2])
+                .to_string()
+        ) || degree_status.overflow_msgs.contains(
+            &messages::medicine_preclinical_course_repetitions_error_msg(vec![cs2, cs1])
+                .to_string()
+        )
     );
 
     // The student repeated a course 3 times
     assert!(degree_status.overflow_msgs.contains(
-        &messages::medicine_preclinical_total_repetitions_error_msg(3)
+        &messages::medicine_preclinical_total_repetitions_error_msg(
+            3,
+            consts::medicine::PRECLINICAL_TOTAL_REPETITIONS_LIMIT
+        )
+        .to_string()
     ));
 
     // ------------------------------------------------------------------------------------------------
@@ -886,9 +1757,10 @@ async fn test_postprocessing_medicine_requirement() {
     degree_status =
         run_degree_status(degree_status, get_catalog(MEDICINE_18_19_CATALOG_ID).await).await;
 
-    assert!(degree_status
-        .overflow_msgs
-        .contains(&messages::medicine_preclinical_avg_error_msg(70.0)));
+    assert!(degree_status.overflow_msgs.contains(
+        &messages::medicine_preclinical_avg_error_msg(70.0, consts::medicine::PRECLINICAL_MIN_AVG)
+            .to_string()
+    ));
 
     // ------------------------------------------------------------------------------------------------
     // verify that the algorithm takes only the highest grades
@@ -916,9 +1788,10 @@ async fn test_postprocessing_medicine_requirement() {
     degree_status =
         run_degree_status(degree_status, get_catalog(MEDICINE_18_19_CATALOG_ID).await).await;
 
-    assert!(!degree_status
-        .overflow_msgs
-        .contains(&messages::medicine_preclinical_avg_error_msg(75.0)));
+    assert!(!degree_status.overflow_msgs.contains(
+        &messages::medicine_preclinical_avg_error_msg(75.0, consts::medicine::PRECLINICAL_MIN_AVG)
+            .to_string()
+    ));
 }
 
 #[test]
@@ -967,10 +1840,10 @@ async fn test_software_engineer_itinerary() {
     );
     assert_eq!(
         degree_status.course_bank_requirements[5].message,
-        Some(messages::completed_chain_msg(vec![
-            "פיסיקה 2".to_string(),
-            "פיסיקה 3".to_string()
-        ]))
+        Some(
+            messages::completed_chain_msg(vec!["פיסיקה 2".to_string(), "פיסיקה 3".to_string()])
+                .to_string()
+        )
     );
 
     assert_eq!(
@@ -1002,15 +1875,15 @@ async fn test_software_engineer_itinerary() {
 
     assert_eq!(
         degree_status.overflow_msgs[0],
-        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א")
+        messages::credit_overflow_detailed_msg("פרויקט", "רשימה א").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[1],
-        messages::credit_overflow_msg(2.0, "שרשרת מדעית", "רשימה ב")
+        messages::credit_overflow_msg(2.0, "שרשרת מדעית", "רשימה ב").to_string()
     );
     assert_eq!(
         degree_status.overflow_msgs[2],
-        messages::credit_leftovers_msg(0.0)
+        messages::credit_leftovers_msg(0.0).to_string()
     );
 }
 
@@ -1027,6 +1900,7 @@ async fn test_catalog_validations() {
     catalog.credit_overflows.push(CreditOverflow {
         from: "בחירה חופשית".to_string(),
         to: "רשימה א".to_string(),
+        ..Default::default()
     });
 
     let result = validate_catalog(&catalog);
@@ -1038,3 +1912,676 @@ async fn test_catalog_validations() {
         )
     }
 }
+
+#[test]
+async fn test_faculty_rules() {
+    let compute = |faculty: Faculty| {
+        let mut degree_status = create_degree_status();
+        degree_status
+            .compute(
+                Catalog {
+                    faculty,
+                    ..create_catalog()
+                },
+                COURSES.clone(),
+            )
+            .unwrap();
+        degree_status
+    };
+
+    let computer_science = compute(Faculty::ComputerScience);
+    assert!(computer_science.summary.unmet_conditions.is_empty());
+
+    // A faculty without any rules of its own computes exactly like the computer science faculty
+    let data_science = compute(Faculty::DataAndDecisionScience);
+    assert_eq!(
+        serde_json::to_string(&data_science).unwrap(),
+        serde_json::to_string(&computer_science).unwrap()
+    );
+
+    // The medicine rules report the preclinical average even when there are no preclinical courses
+    let medicine = compute(Faculty::Medicine);
+    assert_eq!(
+        medicine.typed_overflow_msgs.len(),
+        computer_science.typed_overflow_msgs.len() + 1
+    );
+    assert!(matches!(
+        medicine.typed_overflow_msgs.last(),
+        Some(messages::Message::MedicinePreclinicalAvg { .. })
+    ));
+}
+
+#[test]
+async fn test_catalog_english_requirement() {
+    let english_course_status = |id: &str| CourseStatus {
+        course: Course {
+            id: id.to_string(),
+            credit: 2.0,
+            name: "english content".to_string(),
+            tags: Some(vec![Tag::English]),
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        ..Default::default()
+    };
+    let catalog = Catalog {
+        name: "2022-2023".to_string(),
+        english_requirement: Some(EnglishRequirement {
+            level_course: "324099".to_string(),
+            exempt_courses_count: 3,
+            courses_count: 1,
+            from_year: Some(2022),
+            until_year: None,
+        }),
+        ..create_catalog()
+    };
+    let mut degree_status = create_degree_status();
+    degree_status.course_statuses.extend([
+        CourseStatus {
+            course: Course {
+                id: "324099".to_string(),
+                name: "level course".to_string(),
+                ..Default::default()
+            },
+            state: Some(CourseState::Complete),
+            grade: Some(Grade::ExemptionWithoutCredit),
+            ..Default::default()
+        },
+        english_course_status("324100"),
+        english_course_status("324101"),
+    ]);
+
+    // The student is exempt from the level course, so the catalog demands 3 english taught courses
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+    let requirement = degree_status
+        .course_bank_requirements
+        .iter()
+        .find(|requirement| requirement.course_bank_name == ENGLISH_REQUIREMENT_NAME)
+        .unwrap();
+    assert_eq!(requirement.course_requirement, Some(3));
+    assert_eq!(requirement.course_completed, 2);
+    assert!(!requirement.completed);
+    assert_eq!(
+        requirement.typed_message,
+        Some(messages::english_requirement_for_exempt_students_msg(3))
+    );
+    // The missing requirement is counted once in the summary
+    assert_eq!(
+        degree_status
+            .summary
+            .incomplete_banks
+            .iter()
+            .filter(|bank| *bank == ENGLISH_REQUIREMENT_NAME)
+            .count(),
+        1
+    );
+    assert!(degree_status.summary.unmet_conditions.is_empty());
+    assert!(!degree_status.summary.completed);
+
+    degree_status
+        .course_statuses
+        .push(english_course_status("324102"));
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+    let requirement = degree_status
+        .course_bank_requirements
+        .iter()
+        .find(|requirement| requirement.course_bank_name == ENGLISH_REQUIREMENT_NAME)
+        .unwrap();
+    assert!(requirement.completed);
+    assert!(degree_status.summary.unmet_conditions.is_empty());
+
+    // The requirement doesn't apply to catalogs before its first year
+    degree_status
+        .compute(
+            Catalog {
+                name: "2021-2022".to_string(),
+                ..catalog
+            },
+            COURSES.clone(),
+        )
+        .unwrap();
+    assert!(degree_status
+        .course_bank_requirements
+        .iter()
+        .all(|requirement| requirement.course_bank_name != ENGLISH_REQUIREMENT_NAME));
+}
+
+#[test]
+async fn test_medicine_clinical_rules() {
+    let clinical_course_status = |id: &str, grade: u32, times_repeated: usize| CourseStatus {
+        course: Course {
+            id: id.to_string(),
+            credit: 4.0,
+            name: format!("clinical {id}"),
+            tags: Some(vec![Tag::MedicineClinical]),
+        },
+        state: Some(CourseState::Complete),
+        semester: Some("חורף_1".to_string()),
+        grade: Some(Grade::Numeric(grade)),
+        times_repeated,
+        ..Default::default()
+    };
+    let catalog = create_medicine_catalog(MedicinePolicy {
+        clinical_min_avg: 80.0,
+        clinical_rotations: vec!["270001".to_string(), "270003".to_string()],
+        ..Default::default()
+    });
+
+    // Students who didn't start the clinical years are not checked for the clinical rules
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+    assert!(degree_status.summary.unmet_conditions.is_empty());
+
+    degree_status.course_statuses.extend([
+        clinical_course_status("270001", 70, 0),
+        clinical_course_status("270002", 80, 1),
+    ]);
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+    let clinical_course = degree_status.get_course_status("270002").unwrap();
+    assert_eq!(
+        degree_status.summary.unmet_conditions,
+        vec![
+            messages::medicine_clinical_avg_error_msg(75.0, 80.0).to_string(),
+            messages::medicine_clinical_course_repetitions_error_msg(vec![clinical_course])
+                .to_string(),
+            messages::medicine_incomplete_rotations_msg(vec!["270003".to_string()]).to_string(),
+        ]
+    );
+
+    // The thresholds are taken from the catalog
+    degree_status
+        .compute(
+            Catalog {
+                medicine_policy: MedicinePolicy {
+                    clinical_course_repetitions_limit: 2,
+                    ..Default::default()
+                },
+                ..catalog
+            },
+            COURSES.clone(),
+        )
+        .unwrap();
+    assert!(degree_status.summary.unmet_conditions.is_empty());
+    assert!(degree_status
+        .typed_overflow_msgs
+        .contains(&messages::medicine_clinical_avg_msg(75.0)));
+}
+
+#[test]
+async fn test_medicine_preclinical_rules_ignore_clinical_courses() {
+    let mut catalog = create_medicine_catalog(MedicinePolicy::default());
+    // A clinical course which belongs to a preclinical bank
+    catalog.course_to_bank.insert(
+        "270001".to_string(),
+        consts::medicine::ALL_BANK_NAME.to_string(),
+    );
+
+    let mut degree_status = create_degree_status();
+    degree_status.course_statuses.push(CourseStatus {
+        course: Course {
+            id: "270001".to_string(),
+            credit: 4.0,
+            name: "clinical 270001".to_string(),
+            tags: Some(vec![Tag::MedicineClinical]),
+        },
+        state: Some(CourseState::Complete),
+        semester: Some("חורף_1".to_string()),
+        grade: Some(Grade::Numeric(50)),
+        times_repeated: 3,
+        ..Default::default()
+    });
+    degree_status.compute(catalog, COURSES.clone()).unwrap();
+
+    // The grade and the repetitions of the clinical course are checked only by the clinical rules
+    assert!(degree_status
+        .typed_overflow_msgs
+        .contains(&messages::medicine_preclinical_avg_msg(85.0)));
+    let clinical_course = degree_status.get_course_status("270001").unwrap();
+    assert_eq!(
+        degree_status.summary.unmet_conditions,
+        vec![
+            messages::medicine_clinical_avg_error_msg(50.0, consts::medicine::CLINICAL_MIN_AVG)
+                .to_string(),
+            messages::medicine_clinical_course_repetitions_error_msg(vec![clinical_course])
+                .to_string(),
+        ]
+    );
+}
+
+#[test]
+async fn test_catalog_report() {
+    let mut catalog = create_catalog();
+    catalog.total_credit = 11.5;
+    let courses = COURSES.values().cloned().collect::<Vec<_>>();
+    let report = catalog_report(&catalog, Some(&courses));
+    assert!(report.is_valid());
+    assert!(report.findings.is_empty());
+
+    catalog
+        .course_to_bank
+        .insert("114052".to_string(), "unknown".to_string());
+    catalog.course_banks.push(CourseBank {
+        name: "specialization".to_string(),
+        rule: Rule::SpecializationGroups(SpecializationGroups {
+            groups_list: vec![SpecializationGroup {
+                name: "group".to_string(),
+                courses_sum: 1,
+                course_list: vec!["236303".to_string()],
+                mandatory: Some(vec![vec!["236512".to_string()]]),
+            }],
+            groups_number: 2,
+        }),
+        credit: None,
+    });
+    catalog
+        .common_replacements
+        .insert("104031".to_string(), vec!["999999".to_string()]);
+
+    let report = catalog_report(&catalog, Some(&courses));
+    assert!(!report.is_valid());
+    let errors = report
+        .errors()
+        .map(|finding| finding.message.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            messages::unknown_bank_for_course("114052", "unknown"),
+            messages::too_many_required_specialization_groups("specialization", 2, 1),
+            messages::course_not_in_bank("236303", "specialization"),
+            messages::mandatory_course_not_in_specialization_group("236512", "group"),
+        ]
+    );
+    let warnings = report
+        .findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Warning)
+        .map(|finding| finding.message.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![messages::unknown_course_in_replacement("104031", "999999")]
+    );
+    // All the errors are reported, not only the first one
+    assert_eq!(
+        validate_catalog(&catalog).unwrap_err().to_string(),
+        errors.join("\n")
+    );
+}
+
+#[test]
+async fn test_invalid_credit_overflows() {
+    let mut catalog = create_catalog();
+    catalog.credit_overflows[0].ratio = Some(0.0);
+    catalog.credit_overflows[0].cap = Some(-1.0);
+    let errors = catalog_report(&catalog, None)
+        .errors()
+        .map(|finding| finding.message.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            messages::invalid_credit_overflow_ratio("hova", "reshima", 0.0),
+            messages::invalid_credit_overflow_cap("hova", "reshima", -1.0),
+        ]
+    );
+
+    catalog.credit_overflows[0].ratio = Some(-0.5);
+    catalog.credit_overflows[0].cap = Some(0.0);
+    assert!(validate_catalog(&catalog).is_err());
+
+    // A cap of 0 discards the overflow, which is allowed
+    catalog.credit_overflows[0].ratio = Some(0.5);
+    assert!(validate_catalog(&catalog).is_ok());
+
+    // Only one transfer between the same banks is allowed
+    catalog.credit_overflows.push(CreditOverflow {
+        from: "hova".to_string(),
+        to: "reshima".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(
+        validate_catalog(&catalog).unwrap_err().to_string(),
+        messages::duplicate_credit_overflow("hova", "reshima")
+    );
+}
+
+#[test]
+async fn test_catalog_total_credit() {
+    let warnings = |catalog: &Catalog| {
+        catalog_report(catalog, None)
+            .findings
+            .into_iter()
+            .filter(|finding| finding.severity == Severity::Warning)
+            .map(|finding| finding.message)
+            .collect::<Vec<_>>()
+    };
+    let mut catalog = create_catalog();
+    catalog.total_credit = 12.0;
+    assert_eq!(
+        warnings(&catalog),
+        vec![messages::banks_credit_mismatch(11.5, 12.0)]
+    );
+
+    // Sums of credit which aren't exact in floating point are still equal
+    catalog.total_credit = 11.7;
+    catalog.course_banks[0].credit = Some(8.4);
+    catalog.course_banks[1].credit = Some(3.3);
+    assert!(warnings(&catalog).is_empty());
+
+    // The total can't be compared when a bank has no credit requirement
+    catalog.course_banks[1].credit = None;
+    assert!(warnings(&catalog).is_empty());
+}
+
+#[test]
+async fn test_catalog_diff() {
+    let old = create_catalog();
+    let mut new = old.clone();
+    assert_eq!(catalog_diff::diff(&old, &new), Default::default());
+
+    new.course_banks[1].credit = Some(5.0);
+    new.course_banks.push(CourseBank {
+        name: "bechira".to_string(),
+        rule: Rule::Elective,
+        credit: Some(2.0),
+    });
+    new.course_to_bank
+        .insert("236303".to_string(), "reshima".to_string());
+    new.course_to_bank.remove("104031");
+    new.course_to_bank
+        .insert("114052".to_string(), "bechira".to_string());
+
+    let diff = catalog_diff::diff(&old, &new);
+    assert_eq!(diff.added_banks, vec!["bechira".to_string()]);
+    assert!(diff.removed_banks.is_empty());
+    assert_eq!(
+        diff.changed_banks,
+        vec![BankChange {
+            bank_name: "reshima".to_string(),
+            old_rule: Rule::AccumulateCredit,
+            new_rule: Rule::AccumulateCredit,
+            old_credit: Some(3.0),
+            new_credit: Some(5.0),
+        }]
+    );
+    assert_eq!(
+        diff.moved_courses,
+        vec![
+            CourseMove {
+                course_id: "104031".to_string(),
+                from_bank: Some("hova".to_string()),
+                to_bank: None,
+            },
+            CourseMove {
+                course_id: "114052".to_string(),
+                from_bank: None,
+                to_bank: Some("bechira".to_string()),
+            },
+            CourseMove {
+                course_id: "236303".to_string(),
+                from_bank: Some("hova".to_string()),
+                to_bank: Some("reshima".to_string()),
+            },
+        ]
+    );
+
+    // The diff in the other direction removes what was added
+    let reversed_diff = catalog_diff::diff(&new, &old);
+    assert_eq!(reversed_diff.removed_banks, vec!["bechira".to_string()]);
+    assert!(reversed_diff.added_banks.is_empty());
+}
+
+#[test]
+async fn test_catalog_changes() {
+    let mut catalog = create_catalog();
+    catalog.name = "2022-2023".to_string();
+    catalog.start_year = Some(2022);
+    catalog.course_banks.push(CourseBank {
+        name: "chain".to_string(),
+        rule: Rule::Chains(vec![vec!["114052".to_string(), "114054".to_string()]]),
+        credit: Some(8.0),
+    });
+    catalog
+        .course_to_bank
+        .insert("114052".to_string(), "chain".to_string());
+    catalog
+        .course_to_bank
+        .insert("114054".to_string(), "chain".to_string());
+    catalog
+        .catalog_replacements
+        .insert("236303".to_string(), vec!["114052".to_string()]);
+
+    let changes = CatalogChanges::from_toml(
+        r#"
+name = "2023-2024"
+removed_courses = ["104031"]
+
+[renumbered_courses]
+114052 = "114071"
+
+[moved_courses]
+236303 = "reshima"
+104032 = "hova"
+
+[bank_credits]
+reshima = 4.5
+"#,
+    )
+    .expect("Fail to parse changes");
+    let adapted = changes.apply(&catalog).expect("Fail to apply changes");
+
+    assert_ne!(adapted.id, catalog.id);
+    assert_eq!(adapted.name, "2023-2024");
+    assert_eq!(adapted.start_year, Some(2023));
+    assert_eq!(
+        adapted.course_to_bank,
+        BTreeMap::from([
+            ("104032".to_string(), "hova".to_string()),
+            ("114054".to_string(), "chain".to_string()),
+            ("114071".to_string(), "chain".to_string()),
+            ("236303".to_string(), "reshima".to_string()),
+        ])
+    );
+    assert_eq!(
+        adapted.course_banks[2].rule,
+        Rule::Chains(vec![vec!["114071".to_string(), "114054".to_string()]])
+    );
+    assert_eq!(
+        adapted.catalog_replacements["236303"],
+        vec!["114071".to_string()]
+    );
+    assert_eq!(adapted.course_banks[1].credit, Some(4.5));
+    assert!(validate_catalog(&adapted).is_ok());
+    // The original catalog is not changed
+    assert_eq!(catalog.course_to_bank["114052"], "chain");
+
+    // Changes which refer to courses and banks which are not part of the catalog are rejected
+    let changes = CatalogChanges::from_toml(
+        r#"
+name = "2023-2024"
+[renumbered_courses]
+999999 = "999998"
+[bank_credits]
+unknown = 2.0
+"#,
+    )
+    .expect("Fail to parse changes");
+    assert!(changes.apply(&catalog).is_err());
+}
+
+#[test]
+async fn test_catalog_year_and_track() {
+    let mut catalog = Catalog {
+        name: "מדמח תלת שנתי 2019-2020".to_string(),
+        ..Default::default()
+    };
+    // Catalogs which were not backfilled still get their year from their name
+    assert_eq!(catalog.year(), 2019);
+
+    assert!(catalog.backfill_from_name());
+    assert_eq!(catalog.start_year, Some(2019));
+    assert_eq!(catalog.track_years, Some(3));
+    assert_eq!(catalog.track_code.as_deref(), Some("מדמח תלת שנתי"));
+    // Running the backfill again changes nothing
+    assert!(!catalog.backfill_from_name());
+
+    // The structured start year takes precedence over the name
+    catalog.start_year = Some(2022);
+    assert_eq!(catalog.year(), 2022);
+    assert!(!catalog.backfill_from_name());
+    assert_eq!(catalog.start_year, Some(2022));
+
+    let mut catalog = Catalog {
+        name: "catalog without a year".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(catalog.year(), 2018);
+    assert!(catalog.backfill_from_name());
+    assert_eq!(catalog.start_year, None);
+    assert_eq!(catalog.track_years, None);
+}
+
+#[test]
+async fn test_regression_corpus() {
+    let results = regression::run_corpus(std::path::Path::new("../docs/regression"))
+        .expect("Fail to run the regression corpus");
+    assert!(!results.is_empty());
+
+    let failures = results
+        .iter()
+        .filter(|result| !result.diff.is_empty())
+        .map(|result| format!("{}:\n{}", result.name, result.diff.join("\n")))
+        .collect::<Vec<_>>();
+    assert!(
+        failures.is_empty(),
+        "degree statuses differ from their golden files (run with {}=1 to update them):\n{}",
+        regression::UPDATE_GOLDEN_ENV,
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+async fn test_regression_json_diff() {
+    let expected = serde_json::json!({"a": 1, "b": [1, 2], "c": {"d": "x"}});
+    let actual = serde_json::json!({"a": 2, "b": [1], "c": {"d": "x", "e": true}});
+    let mut diff = Vec::new();
+    regression::json_diff("", &expected, &actual, &mut diff);
+    assert_eq!(diff, vec!["~ .a: 1 -> 2", "- .b[1]: 2", "+ .c.e: true"]);
+}
+
+#[test]
+async fn test_credit_transfer_graph_export() {
+    let mut catalog = create_catalog();
+    catalog.course_banks.push(CourseBank {
+        name: "bchira".to_string(),
+        rule: Rule::Elective,
+        credit: Some(2.0),
+    });
+    catalog.credit_overflows.push(CreditOverflow {
+        from: "hova".to_string(),
+        to: "bchira".to_string(),
+        cap: Some(1.0),
+        ..Default::default()
+    });
+
+    let mut graph = CreditTransferGraph::new(&catalog).expect("Fail to build graph");
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges[1].priority, 1);
+    assert!(graph.edges[0].transfers.is_none());
+
+    // Credit of a bank without credit requirement is transferred without a message, and is annotated as well
+    catalog.course_banks[0].credit = None;
+    let mut degree_status = create_degree_status();
+    degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .unwrap();
+    assert!(!degree_status
+        .typed_overflow_msgs
+        .iter()
+        .any(|msg| matches!(msg, messages::Message::CreditOverflow { .. })));
+    graph.annotate(&degree_status);
+    assert_eq!(
+        graph.edges[0].transfers,
+        Some(Transfers {
+            credit: 3.0,
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        graph.edges[1].transfers,
+        Some(Transfers {
+            credit: 1.0,
+            discarded: 4.5,
+            ..Default::default()
+        })
+    );
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph {"));
+    assert!(dot.contains(r#"label = "hova\nall (8.5)""#));
+    assert!(dot.contains(r#"label = "priority 1\ncap 1\ntransferred 1\ndiscarded 4.5""#));
+
+    // Quotes and backslashes in bank names are escaped
+    catalog.course_banks[1].name = r#"re\"shima"#.to_string();
+    catalog.credit_overflows[0].to = catalog.course_banks[1].name.clone();
+    let dot = CreditTransferGraph::new(&catalog)
+        .expect("Fail to build graph")
+        .to_dot();
+    assert!(dot.contains(r#"label = "re\\\"shima\naccumulate credit (3)""#));
+
+    // Overflows between banks which don't exist are rejected
+    catalog.credit_overflows[0].to = "unknown".to_string();
+    assert!(CreditTransferGraph::new(&catalog).is_err());
+}
+
+#[test]
+async fn test_corrupted_catalog() {
+    let mut catalog = create_catalog();
+    catalog.credit_overflows.push(CreditOverflow {
+        from: "reshima".to_string(),
+        to: "hova".to_string(),
+        ..Default::default()
+    });
+    let mut degree_status = create_degree_status();
+    let err = degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .expect_err("A catalog with cyclic credit overflows must be rejected");
+    assert_eq!(err.catalog_id, catalog.id);
+    assert!(matches!(err.error, CreditTransferError::Cycle(_)));
+    // No status is computed against the corrupted catalog
+    assert!(degree_status.course_bank_requirements.is_empty());
+
+    catalog.credit_overflows[1].to = "unknown".to_string();
+    let err = degree_status
+        .compute(catalog.clone(), COURSES.clone())
+        .expect_err("A catalog with credit overflows to unknown banks must be rejected");
+    assert_eq!(err.error, CreditTransferError::UnknownBank);
+
+    // A corrupted secondary catalog fails the whole computation
+    let err = degree_status
+        .compute_with_secondary_catalogs(
+            create_catalog(),
+            vec![(catalog.clone(), CourseSharing::default())],
+            COURSES.clone(),
+        )
+        .expect_err("A corrupted secondary catalog must be rejected");
+    assert_eq!(err.catalog_id, catalog.id);
+    assert!(validate_computable_catalog(&catalog).is_err());
+
+    // Findings which don't stop the computation don't stop students from choosing the catalog
+    let mut catalog = create_catalog();
+    catalog
+        .course_to_bank
+        .insert("114052".to_string(), "unknown".to_string());
+    assert!(validate_catalog(&catalog).is_err());
+    assert!(validate_computable_catalog(&catalog).is_ok());
+}
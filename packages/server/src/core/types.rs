@@ -1,8 +1,9 @@
+use crate::core::messages::Message;
 use crate::resources::catalog::OptionalReplacements;
 use crate::resources::course::CourseId;
 use bson::doc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub type Chain = Vec<CourseId>;
 pub type NumCourses = usize;
@@ -41,9 +42,9 @@ pub enum Rule {
     Wildcard(bool), // קלף משוגע עבור להתמודד עם
 }
 
-impl ToString for Rule {
-    fn to_string(&self) -> String {
-        match self {
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let rule = match self {
             Rule::All => "all",
             Rule::AccumulateCredit => "accumulate credit",
             Rule::AccumulateCourses(_) => "accumulate courses",
@@ -53,15 +54,40 @@ impl ToString for Rule {
             Rule::Chains(_) => "chains",
             Rule::SpecializationGroups(_) => "specialization groups",
             Rule::Wildcard(_) => "wildcard",
-        }
-        .into()
+        };
+        write!(f, "{}", rule)
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct CreditOverflow {
     pub from: String,
     pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cap: Option<f32>, // The maximal credit which may be counted in the target bank, the rest is discarded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratio: Option<f32>, // Each transferred credit point is counted as `ratio` points in the target bank
+}
+
+impl CreditOverflow {
+    pub fn ratio(&self) -> f32 {
+        self.ratio.unwrap_or(1.0)
+    }
+
+    // Returns the credit which is counted in the target bank for the given overflow
+    pub fn transferred_credit(&self, overflow: f32) -> f32 {
+        let converted = overflow * self.ratio();
+        self.cap.map_or(converted, |cap| converted.min(cap))
+    }
+
+    // Returns the part of the given overflow (in the credit of the source bank) which can't be transferred due to the cap
+    pub fn discarded_credit(&self, overflow: f32) -> f32 {
+        let ratio = self.ratio();
+        if ratio <= 0.0 {
+            return overflow;
+        }
+        (overflow - self.transferred_credit(overflow) / ratio).max(0.0)
+    }
 }
 
 pub enum Transfer {
@@ -70,7 +96,25 @@ pub enum Transfer {
     CoursesOverflow,
 }
 
-#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+// What was moved by a transfer between two banks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TransferKind {
+    Credit,
+    MissingCredit, // Credit requirement which was moved to the target because the source bank has fewer credit than required
+    Courses,
+    DiscardedCredit, // Credit which exceeded the cap of the overflow, in the credit of the source bank
+}
+
+// A transfer which was made between two banks while computing the degree status
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BankTransfer {
+    pub from: String,
+    pub to: String,
+    pub amount: f32,
+    pub kind: TransferKind,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Requirement {
     pub course_bank_name: String,
     pub bank_rule_name: String,
@@ -80,6 +124,8 @@ pub struct Requirement {
     pub course_completed: usize,
     pub completed: bool, //Did the user complete the necessary demands for this bank
     pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typed_message: Option<Message>,
 }
 impl Requirement {
     pub fn credit_requirement(&mut self, credit: f32) -> &mut Self {
@@ -102,13 +148,33 @@ impl Requirement {
         self.completed = completed;
         self
     }
-    pub fn message(&mut self, message: String) -> &mut Self {
-        self.message = Some(message);
+    pub fn message(&mut self, message: Message) -> &mut Self {
+        self.message = Some(message.to_string());
+        self.typed_message = Some(message);
         self
     }
 }
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct DegreeSummary {
+    pub completed: bool, // Did the user complete all the demands of the degree
+    pub remaining_credit: f32,
+    #[serde(default)]
+    pub reduced_credit: f32, // The credit by which the degree requirement was reduced, due to exemptions without credit
+    pub incomplete_banks: Vec<String>,
+    pub unmet_conditions: Vec<String>, // Demands which are not part of any bank, for example the medicine averages
+    pub estimated_semesters_left: Option<usize>, // Based on the average credit the user completed in a semester
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct SharedCourse {
+    pub course_id: CourseId,
+    pub secondary_catalog: String,
+    pub primary_bank: String,
+    pub secondary_bank: Option<String>, // None if the course may not count towards the secondary catalog as well
+}
+
 pub struct CreditInfo {
     pub sum_credit: f32,
     pub count_courses: usize,
-    pub handled_courses: HashMap<CourseId, CourseId>, // A mapping between course in bank course list, to the course which was done by the user (equal unless there was a replacement)
+    pub handled_courses: BTreeMap<CourseId, CourseId>, // A mapping between course in bank course list, to the course which was done by the user (equal unless there was a replacement)
 }
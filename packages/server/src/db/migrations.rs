@@ -0,0 +1,26 @@
+use crate::error::AppError;
+use crate::resources::catalog::Catalog;
+
+use super::Db;
+
+// Migrations run when the server starts, so each of them must be safe to run more than once
+
+// Fills the structured year and track fields of catalogs which were created before these fields existed.
+// Catalog versions are not backfilled, since published versions never change.
+// Returns the number of catalogs which were updated.
+pub async fn backfill_catalog_years(db: &Db) -> Result<usize, AppError> {
+    let mut updated = 0;
+    for mut catalog in db.get_all::<Catalog>().await? {
+        if catalog.backfill_from_name() {
+            db.update::<Catalog>(catalog).await?;
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+pub async fn run(db: &Db) -> Result<(), AppError> {
+    let updated = backfill_catalog_years(db).await?;
+    log::info!("Backfilled the year and track of {updated} catalogs");
+    Ok(())
+}
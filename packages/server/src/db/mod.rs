@@ -4,6 +4,7 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{config::CONFIG, error::AppError};
 
+pub mod migrations;
 pub mod services;
 
 #[cfg(test)]
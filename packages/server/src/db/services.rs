@@ -96,6 +96,60 @@ impl Db {
         self._update::<R>(resource, InsertOption::SetOnInsert).await
     }
 
+    // Sets a single field of the resource, without replacing the rest of it
+    pub async fn set_field<R>(
+        &self,
+        id: impl Serialize,
+        field: impl AsRef<str>,
+        value: impl Serialize,
+    ) -> Result<(), AppError>
+    where
+        R: Resource + Send + Sync + Unpin,
+    {
+        let id = bson::to_bson(&id)?;
+        Ok(self
+            .client()
+            .database(CONFIG.profile)
+            .collection::<R>(R::collection_name())
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {field.as_ref(): to_bson(&value)?}},
+                None,
+            )
+            .await
+            .map(|_| ())?) // Discard the result of the update operation
+    }
+
+    // Atomically increments a numeric field of the resource, creating the resource if it doesn't exist
+    pub async fn increment_field<R>(
+        &self,
+        id: impl Serialize,
+        field: impl AsRef<str>,
+    ) -> Result<R, AppError>
+    where
+        R: Resource + Send + Sync + Unpin,
+    {
+        let id = bson::to_bson(&id)?;
+        self.client()
+            .database(CONFIG.profile)
+            .collection::<R>(R::collection_name())
+            .find_one_and_update(
+                doc! {"_id": id},
+                UpdateModifications::Document(doc! {"$inc": {field.as_ref(): 1_i64}}),
+                Some(
+                    FindOneAndUpdateOptions::builder()
+                        .upsert(true)
+                        .return_document(ReturnDocument::After)
+                        .build(),
+                ),
+            )
+            .await?
+            .ok_or_else(|| {
+                // This should never happen, but to avoid unwrapping we return an explicit error
+                AppError::NotFound(R::collection_name().to_string())
+            })
+    }
+
     pub async fn delete<R>(&self, id: impl Serialize) -> Result<(), AppError>
     where
         R: Resource + Send + Sync + Unpin,
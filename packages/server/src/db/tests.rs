@@ -1,7 +1,12 @@
 use crate::{
     config::CONFIG,
     db::{Db, FilterOption},
-    resources::course::Course,
+    resources::{
+        catalog::Catalog,
+        catalog_version::{BrokenCatalogVersion, CatalogVersion, VersionState},
+        course::Course,
+        version::DataVersion,
+    },
 };
 use actix_rt::test;
 use actix_web::{body::MessageBody, http::StatusCode, ResponseError};
@@ -94,3 +99,54 @@ pub async fn test_get_courses_by_filters() {
     assert_eq!(courses[1].name, "אלגברה אמ'");
     assert_eq!(courses[1].id, "104166");
 }
+
+#[test]
+pub async fn test_data_version_counter() {
+    let db = Db::new().await;
+    let id = "test_data_version_counter";
+
+    // A version which was never bumped
+    assert_eq!(DataVersion::get(&db, id).await.unwrap(), 0);
+
+    DataVersion::bump(&db, id).await.unwrap();
+    DataVersion::bump(&db, id).await.unwrap();
+    assert_eq!(DataVersion::get(&db, id).await.unwrap(), 2);
+
+    db.delete::<DataVersion>(id).await.unwrap();
+}
+
+#[test]
+pub async fn test_flag_broken_catalog_version() {
+    let db = Db::new().await;
+    let catalog_id = bson::oid::ObjectId::new();
+    let version = db
+        .update::<CatalogVersion>(CatalogVersion {
+            id: CatalogVersion::id_for(&catalog_id, 1),
+            catalog_id,
+            version: 1,
+            state: VersionState::Published,
+            changelog: String::new(),
+            author: String::new(),
+            timestamp: bson::DateTime::now(),
+            catalog: Catalog {
+                id: catalog_id,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+
+    Catalog::flag_broken(&db, &catalog_id, Some(1), "reason".to_string()).await;
+
+    // The published version is immutable, so the flag is recorded apart from it
+    let stored_version = db.get::<CatalogVersion>(&version.id).await.unwrap();
+    assert_eq!(stored_version.catalog.broken, None);
+    let broken_version = db.get::<BrokenCatalogVersion>(&version.id).await.unwrap();
+    assert_eq!(broken_version.catalog_id, catalog_id);
+    assert_eq!(broken_version.reason, "reason");
+
+    db.delete::<BrokenCatalogVersion>(&version.id)
+        .await
+        .unwrap();
+    db.delete::<CatalogVersion>(&version.id).await.unwrap();
+}
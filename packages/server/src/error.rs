@@ -7,6 +7,7 @@ pub enum AppError {
     Parser(String),         // 400
     Unauthorized(String),   // 401
     NotFound(String),       // 404
+    Conflict(String),       // 409
     InternalServer(String), // 500
     Middleware(String),     // 500
     MongoDriver(String),    // 500
@@ -38,6 +39,7 @@ impl std::fmt::Display for AppError {
             AppError::Parser(e) => format!("Parser error: {e}"),
             AppError::Unauthorized(e) => format!("Permission denied: {e}"),
             AppError::NotFound(e) => format!("{e} not found"),
+            AppError::Conflict(e) => e.to_owned(),
             AppError::InternalServer(e) => e.to_owned(),
             AppError::Middleware(e) => format!("Middleware error: {e}"),
             AppError::MongoDriver(e) => format!("MongoDB driver error: {e}"),
@@ -56,6 +58,7 @@ impl ResponseError for AppError {
                 (StatusCode::UNAUTHORIZED, format!("Permission denied: {e}"))
             }
             AppError::NotFound(e) => (StatusCode::NOT_FOUND, format!("{e} not found")),
+            AppError::Conflict(e) => (StatusCode::CONFLICT, e.to_owned()),
             AppError::InternalServer(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_owned()),
             AppError::Middleware(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -69,6 +72,7 @@ impl ResponseError for AppError {
         let mut res = match status_code {
             StatusCode::BAD_REQUEST => HttpResponse::BadRequest().body(error.clone()),
             StatusCode::NOT_FOUND => HttpResponse::NotFound().body(error.clone()),
+            StatusCode::CONFLICT => HttpResponse::Conflict().body(error.clone()),
             StatusCode::UNAUTHORIZED => HttpResponse::Unauthorized().body(error.clone()),
             StatusCode::INTERNAL_SERVER_ERROR => {
                 HttpResponse::InternalServerError().body(error.clone())
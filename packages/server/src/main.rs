@@ -26,6 +26,11 @@ async fn main() -> std::io::Result<()> {
     // Initialize DB client
     let db = Db::new().await;
 
+    // Run the data migrations. The server can still serve the data as it was before a failed migration
+    if let Err(e) = db::migrations::run(&db).await {
+        log::error!("Failed to run migrations: {e}");
+    }
+
     // Start the server
     HttpServer::new(move || {
         App::new()
@@ -48,11 +53,17 @@ async fn main() -> std::io::Result<()> {
                         scope("/students")
                             .app_data(web::Data::new(Permissions::Student))
                             .service(api::students::get_catalogs)
+                            .service(api::students::get_catalog_details)
+                            .service(api::students::compare_catalogs)
                             .service(api::students::login)
                             .service(api::students::update_catalog)
+                            .service(api::students::update_catalog_version)
+                            .service(api::students::update_secondary_catalogs)
                             .service(api::students::get_courses_by_filter)
                             .service(api::students::add_courses)
                             .service(api::students::compute_degree_status)
+                            .service(api::students::get_history)
+                            .service(api::students::get_history_diff)
                             .service(api::students::update_details)
                             .service(api::students::update_settings),
                     )
@@ -67,7 +78,20 @@ async fn main() -> std::io::Result<()> {
                             .service(api::owners::get_all_courses)
                             .service(api::owners::get_course_by_id)
                             .service(api::owners::create_or_update_course)
-                            .service(api::owners::delete_course),
+                            .service(api::owners::delete_course)
+                            .service(api::owners::get_all_catalogs)
+                            .service(api::owners::get_catalog_by_id)
+                            .service(api::owners::get_catalog_validation)
+                            .service(api::owners::get_credit_transfer_graph)
+                            .service(api::owners::create_or_update_catalog)
+                            .service(api::owners::clone_catalog)
+                            .service(api::owners::get_versions)
+                            .service(api::owners::get_versions_diff)
+                            .service(api::owners::save_draft_version)
+                            .service(api::owners::publish_version)
+                            .service(api::owners::export_catalog)
+                            .service(api::owners::import_catalog)
+                            .service(api::owners::delete_catalog),
                     ),
             )
     })
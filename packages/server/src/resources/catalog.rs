@@ -1,14 +1,27 @@
 use crate::{
-    core::{credit_transfer_graph::find_traversal_order, types::CreditOverflow},
-    db::Resource,
-    resources::course::CourseBank,
+    consts,
+    core::{
+        credit_transfer_graph::{find_traversal_order, CreditTransferError},
+        types::{CreditOverflow, Rule},
+    },
+    db::{Db, Resource},
+    resources::{
+        catalog_version::{BrokenCatalogVersion, CatalogVersion},
+        course::CourseBank,
+    },
 };
-use bson::{doc, Document};
+use bson::{doc, DateTime, Document};
+use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{self, Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use super::course::CourseId;
+use super::course::{Course, CourseId};
+
+lazy_static! {
+    static ref YEAR_RE: Regex = Regex::new(r"(?P<year>\d{4})").unwrap();
+    static ref YEARS_RE: Regex = Regex::new(r"\d{4}(-\d{4})?").unwrap();
+}
 
 pub(crate) type OptionalReplacements = Vec<CourseId>;
 
@@ -21,29 +34,211 @@ pub enum Faculty {
     Medicine,
 }
 
+// Determines which attempt of a repeated course counts
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CountedAttempt {
+    #[default]
+    Last,
+    Best,
+}
+
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct RepetitionPolicy {
+    pub counted_attempt: CountedAttempt,
+    pub failed_attempts_in_average: bool, // Whether failed attempts are taken into account when calculating averages
+}
+
+// Determines what happens to the credit of courses in a "rule all" bank which the student got an exemption without credit for (פטור ללא ניקוד)
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExemptionPolicy {
+    #[default]
+    TransferCredit, // The credit is added to the requirement of the bank which the missing credit of the bank is transferred to
+    ReduceCredit, // The credit requirement of the degree is reduced
+}
+
+// Determines which courses may count towards both the primary catalog and a secondary catalog (double degree or minor)
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CourseSharing {
+    #[default]
+    None, // A course counts towards one catalog only
+    All,
+    Courses(Vec<CourseId>), // Only the listed courses may count towards both catalogs
+}
+
+impl CourseSharing {
+    pub fn allows(&self, course_id: &CourseId) -> bool {
+        match self {
+            CourseSharing::None => false,
+            CourseSharing::All => true,
+            CourseSharing::Courses(course_ids) => course_ids.contains(course_id),
+        }
+    }
+}
+
+// The english requirement of the degree. The english level of the student is determined by the level course,
+// students who got an exemption in it must complete more courses which are taught in english.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EnglishRequirement {
+    pub level_course: CourseId,
+    pub exempt_courses_count: usize, // English taught courses required from students with an exemption in the level course
+    pub courses_count: usize, // English taught courses required from students who completed the level course
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_year: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_year: Option<usize>,
+}
+
+impl EnglishRequirement {
+    // The requirement for catalogs which don't state it, which applies to students who started their studies from 2021
+    pub fn technion_default() -> Option<Self> {
+        Some(EnglishRequirement {
+            level_course: consts::TECHNICAL_ENGLISH_ADVANCED_B.to_string(),
+            exempt_courses_count: consts::EXEMPT_COURSES_COUNT_DEMAND,
+            courses_count: consts::ADVANCED_B_COURSES_COUNT_DEMAND,
+            from_year: Some(consts::MINIMAL_YEAR_FOR_ENGLISH_REQUIREMENT),
+            until_year: None,
+        })
+    }
+
+    pub fn applies_to(&self, year: usize) -> bool {
+        self.from_year.is_none_or(|from_year| from_year <= year)
+            && self.until_year.is_none_or(|until_year| year <= until_year)
+    }
+}
+
+// The thresholds of the medicine faculty, which are checked on top of the course banks of medicine catalogs
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MedicinePolicy {
+    pub preclinical_min_avg: f32,
+    pub preclinical_course_repetitions_limit: usize,
+    pub preclinical_total_repetitions_limit: usize,
+    pub clinical_min_avg: f32,
+    pub clinical_course_repetitions_limit: usize,
+    pub clinical_rotations: Vec<CourseId>, // Rotations which must be completed during the clinical years
+}
+
+impl Default for MedicinePolicy {
+    fn default() -> Self {
+        MedicinePolicy {
+            preclinical_min_avg: consts::medicine::PRECLINICAL_MIN_AVG,
+            preclinical_course_repetitions_limit:
+                consts::medicine::PRECLINICAL_COURSE_REPETITIONS_LIMIT,
+            preclinical_total_repetitions_limit:
+                consts::medicine::PRECLINICAL_TOTAL_REPETITIONS_LIMIT,
+            clinical_min_avg: consts::medicine::CLINICAL_MIN_AVG,
+            clinical_course_repetitions_limit: consts::medicine::CLINICAL_COURSE_REPETITIONS_LIMIT,
+            clinical_rotations: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Catalog {
     #[serde(rename(serialize = "_id", deserialize = "_id"))]
     pub id: bson::oid::ObjectId,
     pub name: String,
     pub faculty: Faculty,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_year: Option<usize>, // The academic year in which the students of the catalog started their studies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_years: Option<usize>, // The nominal length of the track in years
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_code: Option<String>, // Identifies the track across years, for example "מדמח תלת שנתי"
     pub total_credit: f64,
     pub description: String,
     pub course_banks: Vec<CourseBank>,
     pub credit_overflows: Vec<CreditOverflow>,
-    pub course_to_bank: HashMap<CourseId, String>,
-    pub catalog_replacements: HashMap<CourseId, OptionalReplacements>, // All replacements which are mentioned in the catalog
-    pub common_replacements: HashMap<CourseId, OptionalReplacements>, // Common replacement which usually approved by the coordinators
+    pub course_to_bank: BTreeMap<CourseId, String>,
+    pub catalog_replacements: BTreeMap<CourseId, OptionalReplacements>, // All replacements which are mentioned in the catalog
+    pub common_replacements: BTreeMap<CourseId, OptionalReplacements>, // Common replacement which usually approved by the coordinators
+    #[serde(default)]
+    pub repetition_policy: RepetitionPolicy,
+    #[serde(default)]
+    pub exemption_policy: ExemptionPolicy,
+    #[serde(default = "EnglishRequirement::technion_default")]
+    pub english_requirement: Option<EnglishRequirement>, // None if the catalog has no english requirement
+    #[serde(default)]
+    pub medicine_policy: MedicinePolicy, // Relevant only for catalogs of the medicine faculty
+    #[serde(default)]
+    pub broken: Option<String>, // Why degree statuses can't be computed against the catalog. Cleared when a valid catalog is published
+}
+
+// Agrees with the serde defaults, so a catalog which is built in code has the same english requirement as a stored one
+impl Default for Catalog {
+    fn default() -> Self {
+        Catalog {
+            id: Default::default(),
+            name: Default::default(),
+            faculty: Default::default(),
+            start_year: None,
+            track_years: None,
+            track_code: None,
+            total_credit: Default::default(),
+            description: Default::default(),
+            course_banks: Default::default(),
+            credit_overflows: Default::default(),
+            course_to_bank: Default::default(),
+            catalog_replacements: Default::default(),
+            common_replacements: Default::default(),
+            repetition_policy: Default::default(),
+            exemption_policy: Default::default(),
+            english_requirement: EnglishRequirement::technion_default(),
+            medicine_policy: Default::default(),
+            broken: None,
+        }
+    }
+}
+
+// Catalog names look like "מדמח תלת שנתי 2019-2020"
+fn start_year_from_name(name: &str) -> Option<usize> {
+    YEAR_RE
+        .captures(name)
+        .and_then(|cap| cap["year"].parse::<usize>().ok())
+}
+
+fn track_years_from_name(name: &str) -> Option<usize> {
+    [("תלת שנתי", 3), ("ארבע שנתי", 4), ("שש שנתי", 6)]
+        .into_iter()
+        .find(|(track, _)| name.contains(track))
+        .map(|(_, years)| years)
+}
+
+fn track_code_from_name(name: &str) -> Option<String> {
+    let track_code = YEARS_RE.replace_all(name, "").trim().to_string();
+    (!track_code.is_empty()).then_some(track_code)
 }
 
 impl Catalog {
     pub fn year(&self) -> usize {
-        let default_year = 2018;
-        Regex::new(r"(?P<year>\d{4})")
-            .unwrap()
-            .captures(&self.name)
-            .map(|cap| cap["year"].parse::<usize>().unwrap_or(default_year))
-            .unwrap_or(default_year)
+        const DEFAULT_YEAR: usize = 2018;
+        self.start_year
+            .or_else(|| {
+                // Catalogs have a start year since the backfill migration, unless their name has no year either.
+                // Versions which were published before it are never backfilled, so they get here
+                log::debug!("Catalog {} has no start year", self.id);
+                start_year_from_name(&self.name)
+            })
+            .unwrap_or(DEFAULT_YEAR)
+    }
+
+    // Fills the missing year and track fields from the catalog's name. Returns true if any field was filled.
+    pub fn backfill_from_name(&mut self) -> bool {
+        let mut changed = false;
+        for (field, value) in [
+            (&mut self.start_year, start_year_from_name(&self.name)),
+            (&mut self.track_years, track_years_from_name(&self.name)),
+        ] {
+            if field.is_none() && value.is_some() {
+                *field = value;
+                changed = true;
+            }
+        }
+        if self.track_code.is_none() {
+            self.track_code = track_code_from_name(&self.name);
+            changed |= self.track_code.is_some();
+        }
+        changed
     }
 
     pub fn get_course_list(&self, name: &str) -> Vec<CourseId> {
@@ -60,7 +255,24 @@ impl Catalog {
         self.course_banks.iter().find(|bank| bank.name == name)
     }
 
-    pub fn get_bank_traversal_order(&self) -> Vec<CourseBank> {
+    // Returns the credit overflow rules from the given bank, ordered by their priority.
+    // The excess credit of the bank fills each target up to its requirement before spilling to the next one.
+    pub fn get_credit_overflows_from(&self, bank_name: &str) -> Vec<&CreditOverflow> {
+        self.credit_overflows
+            .iter()
+            .filter(|credit_overflow| credit_overflow.from == bank_name)
+            .collect()
+    }
+
+    // Returns true if the excess credit of the rule's source bank has other targets after the rule's target
+    pub fn is_prioritized_credit_overflow(&self, credit_overflow: &CreditOverflow) -> bool {
+        self.get_credit_overflows_from(&credit_overflow.from)
+            .last()
+            .map(|last_credit_overflow| last_credit_overflow.to != credit_overflow.to)
+            .unwrap_or(false)
+    }
+
+    pub fn get_bank_traversal_order(&self) -> Result<Vec<CourseBank>, CreditTransferError> {
         find_traversal_order(self)
     }
 
@@ -68,8 +280,30 @@ impl Catalog {
         self.course_to_bank.clone().into_keys().collect()
     }
 
-    pub fn is_medicine(&self) -> bool {
-        matches!(self.faculty, Faculty::Medicine)
+    // Flags the catalog, or the given version of it, as broken so the owners can see why it can't be used.
+    // The flag is best effort: a failure to write it is only logged, so callers can still report the corrupted catalog.
+    pub async fn flag_broken(
+        db: &Db,
+        catalog_id: &bson::oid::ObjectId,
+        version: Option<u32>,
+        reason: String,
+    ) {
+        log::error!("catalog {catalog_id} is broken: {reason}");
+        let result = match version {
+            Some(version) => db
+                .update::<BrokenCatalogVersion>(BrokenCatalogVersion {
+                    id: CatalogVersion::id_for(catalog_id, version),
+                    catalog_id: *catalog_id,
+                    reason,
+                    timestamp: DateTime::now(),
+                })
+                .await
+                .map(|_| ()),
+            None => db.set_field::<Catalog>(catalog_id, "broken", reason).await,
+        };
+        if let Err(e) = result {
+            log::error!("Failed to flag catalog {catalog_id} as broken: {e}");
+        }
     }
 }
 
@@ -88,17 +322,33 @@ pub struct DisplayCatalog {
     pub id: bson::oid::ObjectId,
     pub name: String,
     pub faculty: Faculty,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_year: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_years: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_code: Option<String>,
     pub total_credit: f64,
     pub description: String,
     pub course_bank_names: Vec<String>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecondaryCatalog {
+    pub catalog: DisplayCatalog,
+    #[serde(default)]
+    pub course_sharing: CourseSharing,
+}
+
 impl From<Catalog> for DisplayCatalog {
     fn from(catalog: Catalog) -> Self {
         DisplayCatalog {
             id: catalog.id,
             name: catalog.name,
             faculty: catalog.faculty,
+            start_year: catalog.start_year,
+            track_years: catalog.track_years,
+            track_code: catalog.track_code,
             total_credit: catalog.total_credit,
             description: catalog.description,
             course_bank_names: catalog
@@ -109,3 +359,55 @@ impl From<Catalog> for DisplayCatalog {
         }
     }
 }
+
+// A course of a catalog, resolved against the courses collection
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogCourse {
+    pub id: CourseId,
+    pub name: Option<String>, // None if the course is not in the courses collection
+    pub credit: Option<f32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CourseBankDetails {
+    pub name: String,
+    pub rule: Rule, // Includes the chains and the specialization groups with their mandatory courses
+    pub credit: Option<f32>,
+    pub courses: Vec<CatalogCourse>,
+}
+
+// The structure of a catalog as it is shown to students
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogDetails {
+    pub catalog: DisplayCatalog,
+    pub course_banks: Vec<CourseBankDetails>,
+    pub credit_overflows: Vec<CreditOverflow>,
+}
+
+impl CatalogDetails {
+    pub fn new(catalog: Catalog, courses: &HashMap<CourseId, Course>) -> Self {
+        let course_banks = catalog
+            .course_banks
+            .iter()
+            .map(|bank| CourseBankDetails {
+                name: bank.name.clone(),
+                rule: bank.rule.clone(),
+                credit: bank.credit,
+                courses: catalog
+                    .get_course_list(&bank.name)
+                    .into_iter()
+                    .map(|course_id| CatalogCourse {
+                        name: courses.get(&course_id).map(|course| course.name.clone()),
+                        credit: courses.get(&course_id).map(|course| course.credit),
+                        id: course_id,
+                    })
+                    .collect(),
+            })
+            .collect();
+        CatalogDetails {
+            course_banks,
+            credit_overflows: catalog.credit_overflows.clone(),
+            catalog: DisplayCatalog::from(catalog),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    core::{
+        messages,
+        types::{CreditOverflow, Rule},
+    },
+    error::AppError,
+};
+
+use super::{
+    catalog::{
+        Catalog, EnglishRequirement, ExemptionPolicy, Faculty, MedicinePolicy,
+        OptionalReplacements, RepetitionPolicy,
+    },
+    course::{CourseBank, CourseId},
+};
+
+// A course bank with its courses and the replacements of its courses, as written in a catalog file
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BankFile {
+    pub name: String,
+    pub rule: Rule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit: Option<f32>,
+    #[serde(default)]
+    pub courses: Vec<CourseId>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub catalog_replacements: BTreeMap<CourseId, OptionalReplacements>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub common_replacements: BTreeMap<CourseId, OptionalReplacements>,
+}
+
+// A readable representation of a catalog, grouped by course banks, which is kept in TOML files so changes can be reviewed as diffs.
+// Converting a catalog to a file and back results in the same catalog.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogFile {
+    pub id: String,
+    pub name: String,
+    pub faculty: Faculty,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_year: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_years: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_code: Option<String>,
+    pub total_credit: f64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub repetition_policy: RepetitionPolicy,
+    #[serde(default)]
+    pub exemption_policy: ExemptionPolicy,
+    #[serde(
+        default = "EnglishRequirement::technion_default",
+        serialize_with = "serialize_english_requirement",
+        deserialize_with = "deserialize_english_requirement"
+    )]
+    pub english_requirement: Option<EnglishRequirement>, // When omitted, the default requirement like in a stored catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medicine_policy: Option<MedicinePolicy>, // Omitted when the catalog uses the default thresholds
+    #[serde(default)]
+    pub credit_overflows: Vec<CreditOverflow>,
+    #[serde(default)]
+    pub banks: Vec<BankFile>,
+    // Replacements of courses which don't belong to any bank
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub catalog_replacements: BTreeMap<CourseId, OptionalReplacements>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub common_replacements: BTreeMap<CourseId, OptionalReplacements>,
+}
+
+const NO_ENGLISH_REQUIREMENT: &str = "none";
+
+// An omitted english requirement is the default one, so a catalog without a requirement is written as "none"
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnglishRequirementFile {
+    Requirement(EnglishRequirement),
+    Marker(String),
+}
+
+fn serialize_english_requirement<S>(
+    english_requirement: &Option<EnglishRequirement>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match english_requirement {
+        Some(english_requirement) => english_requirement.serialize(serializer),
+        None => serializer.serialize_str(NO_ENGLISH_REQUIREMENT),
+    }
+}
+
+fn deserialize_english_requirement<'de, D>(
+    deserializer: D,
+) -> Result<Option<EnglishRequirement>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match EnglishRequirementFile::deserialize(deserializer)? {
+        EnglishRequirementFile::Requirement(english_requirement) => Ok(Some(english_requirement)),
+        EnglishRequirementFile::Marker(marker) if marker == NO_ENGLISH_REQUIREMENT => Ok(None),
+        EnglishRequirementFile::Marker(marker) => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(&marker),
+            &"an english requirement or \"none\"",
+        )),
+    }
+}
+
+impl TryFrom<Catalog> for CatalogFile {
+    type Error = AppError;
+
+    fn try_from(mut catalog: Catalog) -> Result<Self, Self::Error> {
+        // A course of a bank which doesn't exist can't be written under its bank
+        if let Some((course_id, bank_name)) = catalog
+            .course_to_bank
+            .iter()
+            .find(|(_, bank_name)| catalog.get_course_bank_by_name(bank_name).is_none())
+        {
+            return Err(AppError::BadRequest(messages::unknown_bank_for_course(
+                course_id, bank_name,
+            )));
+        }
+
+        let banks = std::mem::take(&mut catalog.course_banks)
+            .into_iter()
+            .map(|bank| {
+                let courses = catalog.get_course_list(&bank.name);
+                let take_replacements =
+                    |replacements: &mut BTreeMap<CourseId, OptionalReplacements>| {
+                        courses
+                            .iter()
+                            .filter_map(|course_id| replacements.remove_entry(course_id))
+                            .collect::<BTreeMap<_, _>>()
+                    };
+                BankFile {
+                    name: bank.name,
+                    rule: bank.rule,
+                    credit: bank.credit,
+                    catalog_replacements: take_replacements(&mut catalog.catalog_replacements),
+                    common_replacements: take_replacements(&mut catalog.common_replacements),
+                    courses,
+                }
+            })
+            .collect();
+
+        Ok(CatalogFile {
+            id: catalog.id.to_hex(),
+            name: catalog.name,
+            faculty: catalog.faculty,
+            start_year: catalog.start_year,
+            track_years: catalog.track_years,
+            track_code: catalog.track_code,
+            total_credit: catalog.total_credit,
+            description: catalog.description,
+            repetition_policy: catalog.repetition_policy,
+            exemption_policy: catalog.exemption_policy,
+            english_requirement: catalog.english_requirement,
+            medicine_policy: (catalog.medicine_policy != MedicinePolicy::default())
+                .then_some(catalog.medicine_policy),
+            credit_overflows: catalog.credit_overflows,
+            banks,
+            catalog_replacements: catalog.catalog_replacements,
+            common_replacements: catalog.common_replacements,
+        })
+    }
+}
+
+impl TryFrom<CatalogFile> for Catalog {
+    type Error = AppError;
+
+    fn try_from(file: CatalogFile) -> Result<Self, Self::Error> {
+        let mut catalog = Catalog {
+            id: bson::oid::ObjectId::from_str(&file.id)?,
+            name: file.name,
+            faculty: file.faculty,
+            start_year: file.start_year,
+            track_years: file.track_years,
+            track_code: file.track_code,
+            total_credit: file.total_credit,
+            description: file.description,
+            credit_overflows: file.credit_overflows,
+            catalog_replacements: file.catalog_replacements,
+            common_replacements: file.common_replacements,
+            repetition_policy: file.repetition_policy,
+            exemption_policy: file.exemption_policy,
+            english_requirement: file.english_requirement,
+            medicine_policy: file.medicine_policy.unwrap_or_default(),
+            ..Default::default()
+        };
+        for bank in file.banks {
+            for course_id in bank.courses {
+                catalog.course_to_bank.insert(course_id, bank.name.clone());
+            }
+            catalog
+                .catalog_replacements
+                .extend(bank.catalog_replacements);
+            catalog.common_replacements.extend(bank.common_replacements);
+            catalog.course_banks.push(CourseBank {
+                name: bank.name,
+                rule: bank.rule,
+                credit: bank.credit,
+            });
+        }
+        Ok(catalog)
+    }
+}
+
+pub fn catalog_to_toml(catalog: Catalog) -> Result<String, AppError> {
+    toml::to_string(&CatalogFile::try_from(catalog)?)
+        .map_err(|e| AppError::InternalServer(e.to_string()))
+}
+
+pub fn catalog_from_toml(toml: &str) -> Result<Catalog, AppError> {
+    toml::from_str::<CatalogFile>(toml)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+        .try_into()
+}
@@ -0,0 +1,67 @@
+use bson::{doc, DateTime, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Resource;
+
+use super::catalog::Catalog;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum VersionState {
+    #[default]
+    Draft, // May still be changed by the owners
+    Published, // Immutable, the catalog which students see is the last published version
+}
+
+// A version of a catalog. Each change of a catalog is saved as a new version, so the changes are traceable
+// and students may keep computing their status against the version they started with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogVersion {
+    #[serde(rename(serialize = "_id", deserialize = "_id"))]
+    pub id: String, // "<catalog id>:<version>"
+    pub catalog_id: bson::oid::ObjectId,
+    pub version: u32,
+    pub state: VersionState,
+    pub changelog: String,
+    pub author: String,      // The owner who created the version
+    pub timestamp: DateTime, // The last time the version was changed
+    pub catalog: Catalog,
+}
+
+impl CatalogVersion {
+    pub fn id_for(catalog_id: &bson::oid::ObjectId, version: u32) -> String {
+        format!("{}:{}", catalog_id.to_hex(), version)
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.state == VersionState::Published
+    }
+}
+
+impl Resource for CatalogVersion {
+    fn collection_name() -> &'static str {
+        "CatalogVersions"
+    }
+    fn key(&self) -> Document {
+        doc! {"_id": self.id.clone()}
+    }
+}
+
+// A version against which degree statuses can't be computed. It is kept apart from the version,
+// since published versions are immutable.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BrokenCatalogVersion {
+    #[serde(rename(serialize = "_id", deserialize = "_id"))]
+    pub id: String, // The id of the version, "<catalog id>:<version>"
+    pub catalog_id: bson::oid::ObjectId,
+    pub reason: String,
+    pub timestamp: DateTime, // The last time the version failed a computation
+}
+
+impl Resource for BrokenCatalogVersion {
+    fn collection_name() -> &'static str {
+        "BrokenCatalogVersions"
+    }
+    fn key(&self) -> Document {
+        doc! {"_id": self.id.clone()}
+    }
+}
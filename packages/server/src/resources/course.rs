@@ -5,9 +5,13 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::iter::FromIterator;
 
+use crate::core::locale::{self, Language};
+use crate::core::messages::Message;
 use crate::core::types::Rule;
 use crate::db::Resource;
 
+use super::catalog::{CountedAttempt, RepetitionPolicy};
+
 pub type CourseId = String;
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
@@ -48,6 +52,10 @@ impl Course {
         self.is(Tag::English)
     }
 
+    pub fn is_medicine_clinical(&self) -> bool {
+        self.is(Tag::MedicineClinical)
+    }
+
     pub fn is_sport(&self) -> bool {
         self.is(Tag::Sport)
     }
@@ -65,17 +73,44 @@ pub enum CourseState {
     Irrelevant,
 }
 
+impl CourseState {
+    pub const ALL: [CourseState; 4] = [
+        CourseState::Complete,
+        CourseState::NotComplete,
+        CourseState::InProgress,
+        CourseState::Irrelevant,
+    ];
+
+    pub fn label(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (CourseState::Complete, Language::Hebrew) => "הושלם",
+            (CourseState::NotComplete, Language::Hebrew) => "לא הושלם",
+            (CourseState::InProgress, Language::Hebrew) => "בתהליך",
+            (CourseState::Irrelevant, Language::Hebrew) => "לא רלוונטי",
+            (CourseState::Complete, Language::English) => "Complete",
+            (CourseState::NotComplete, Language::English) => "Not complete",
+            (CourseState::InProgress, Language::English) => "In progress",
+            (CourseState::Irrelevant, Language::English) => "Irrelevant",
+        }
+    }
+
+    // Parses a label in any of the supported languages
+    pub fn from_label(label: &str) -> Option<CourseState> {
+        CourseState::ALL.into_iter().find(|state| {
+            Language::ALL
+                .into_iter()
+                .any(|language| state.label(language) == label)
+        })
+    }
+}
+
+// Course states are stored in Hebrew, and rendered in the user's language only when they are displayed
 impl Serialize for CourseState {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match *self {
-            CourseState::Complete => serializer.serialize_str("הושלם"),
-            CourseState::NotComplete => serializer.serialize_str("לא הושלם"),
-            CourseState::InProgress => serializer.serialize_str("בתהליך"),
-            CourseState::Irrelevant => serializer.serialize_str("לא רלוונטי"),
-        }
+        serializer.serialize_str(self.label(locale::serialization_language()))
     }
 }
 struct StateStrVisitor;
@@ -91,12 +126,9 @@ impl<'de> Visitor<'de> for StateStrVisitor {
     where
         E: Err,
     {
-        match v {
-            "הושלם" => Ok(CourseState::Complete),
-            "לא הושלם" => Ok(CourseState::NotComplete),
-            "בתהליך" => Ok(CourseState::InProgress),
-            "לא רלוונטי" => Ok(CourseState::Irrelevant),
-            _ => {
+        match CourseState::from_label(v) {
+            Some(state) => Ok(state),
+            None => {
                 let err: E = Err::invalid_type(Unexpected::Str(v), &self);
                 log::error!("Json deserialize error: {}", err.to_string());
                 Err(err)
@@ -114,6 +146,19 @@ impl<'de> Deserialize<'de> for CourseState {
     }
 }
 
+// A single appearance of a course in the student's grade sheet
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Attempt {
+    pub semester: Option<String>,
+    pub grade: Option<Grade>,
+}
+
+impl Attempt {
+    pub fn passed(&self) -> bool {
+        self.grade.map(|grade| grade.passed()).unwrap_or(false)
+    }
+}
+
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct CourseStatus {
     pub course: Course,
@@ -123,22 +168,17 @@ pub struct CourseStatus {
     pub r#type: Option<String>, // if none, nissan cries
     pub specialization_group_name: Option<String>,
     pub additional_msg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typed_additional_msg: Option<Message>,
     pub modified: bool,
     pub times_repeated: usize,
+    #[serde(default)]
+    pub attempts: Vec<Attempt>, // All appearances of the course in the grade sheet, in chronological order
 }
 
 impl CourseStatus {
     pub fn passed(&self) -> bool {
-        match &self.grade {
-            Some(grade) => match grade {
-                Grade::Numeric(grade) => grade >= &55,
-                Grade::Binary(val) => *val,
-                Grade::ExemptionWithoutCredit => true,
-                Grade::ExemptionWithCredit => true,
-                Grade::NotComplete => false,
-            },
-            None => false,
-        }
+        self.grade.map(|grade| grade.passed()).unwrap_or(false)
     }
 
     pub fn completed(&self) -> bool {
@@ -192,13 +232,89 @@ impl CourseStatus {
         self
     }
 
-    pub fn set_msg(&mut self, msg: impl AsRef<str>) -> &mut Self {
-        self.additional_msg = Some(msg.as_ref().to_owned());
+    pub fn set_msg(&mut self, msg: Message) -> &mut Self {
+        self.additional_msg = Some(msg.to_string());
+        self.typed_additional_msg = Some(msg);
         self
     }
     pub fn set_specialization_group_name(&mut self, group_name: impl AsRef<str>) {
         self.specialization_group_name = Some(group_name.as_ref().to_owned());
     }
+
+    fn counted_attempt(&self, counted_attempt: CountedAttempt) -> Option<&Attempt> {
+        let last_attempt = self.attempts.last()?;
+        let graded_attempts = self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.grade.is_some() && attempt.grade != Some(Grade::NotComplete));
+        match counted_attempt {
+            // If the student retook the course and didn't complete it (לא השלים),
+            // the most recent attempt with a grade is the valid one.
+            CountedAttempt::Last if last_attempt.grade == Some(Grade::NotComplete) => {
+                graded_attempts.last().or(Some(last_attempt))
+            }
+            CountedAttempt::Last => Some(last_attempt),
+            // Passed attempts are always preferred, then the highest numeric grade. Grades which aren't numeric
+            // (pass, exemption) rank below numeric ones, and on a tie the latest attempt wins.
+            // If no attempt was passed and the student currently retakes the course, the course is in progress.
+            CountedAttempt::Best => {
+                let rank = |attempt: &Attempt| {
+                    let numeric_grade = match attempt.grade {
+                        Some(Grade::Numeric(grade)) => Some(grade),
+                        _ => None,
+                    };
+                    (attempt.passed(), numeric_grade)
+                };
+                graded_attempts
+                    .filter(|attempt| attempt.passed() || last_attempt.grade.is_some())
+                    .fold(None, |best: Option<&Attempt>, attempt| match best {
+                        Some(best) if rank(best) > rank(attempt) => Some(best),
+                        _ => Some(attempt),
+                    })
+                    .or(Some(last_attempt))
+            }
+        }
+    }
+
+    // Sets the grade and semester of the course according to the attempt which counts by the given policy.
+    // The status is left untouched if the counted attempt is already the current one.
+    pub fn apply_repetition_policy(&mut self, policy: &RepetitionPolicy) {
+        let Some(attempt) = self.counted_attempt(policy.counted_attempt).cloned() else {
+            return;
+        };
+        if attempt.grade != self.grade || attempt.semester != self.semester {
+            self.grade = attempt.grade;
+            self.semester = attempt.semester;
+            self.set_state();
+        }
+        let mut repeated_semesters = self
+            .attempts
+            .iter()
+            .map(|attempt| &attempt.semester)
+            .filter(|semester| **semester != self.semester)
+            .collect::<Vec<_>>();
+        repeated_semesters.dedup();
+        self.times_repeated = repeated_semesters.len();
+    }
+
+    // Returns the numeric grades which should be taken into account when calculating an average
+    pub fn grades_for_average(&self, policy: &RepetitionPolicy) -> Vec<u32> {
+        let Some(Grade::Numeric(grade)) = self.grade else {
+            return vec![];
+        };
+        let failed_attempts_grades = self
+            .attempts
+            .iter()
+            .filter(|attempt| policy.failed_attempts_in_average && !attempt.passed())
+            .filter(|attempt| attempt.semester != self.semester || attempt.grade != self.grade)
+            .filter_map(|attempt| match attempt.grade {
+                Some(Grade::Numeric(grade)) => Some(grade),
+                _ => None,
+            });
+        std::iter::once(grade)
+            .chain(failed_attempts_grades)
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -217,19 +333,66 @@ pub enum Grade {
     NotComplete,
 }
 
+impl Grade {
+    pub fn passed(&self) -> bool {
+        match self {
+            Grade::Numeric(grade) => *grade >= 55,
+            Grade::Binary(val) => *val,
+            Grade::ExemptionWithoutCredit => true,
+            Grade::ExemptionWithCredit => true,
+            Grade::NotComplete => false,
+        }
+    }
+}
+
+impl Grade {
+    // All grades which are not numeric
+    const LABELED: [Grade; 5] = [
+        Grade::Binary(true),
+        Grade::Binary(false),
+        Grade::ExemptionWithoutCredit,
+        Grade::ExemptionWithCredit,
+        Grade::NotComplete,
+    ];
+
+    pub fn label(&self, language: Language) -> String {
+        match (self, language) {
+            (Grade::Numeric(grade), _) => grade.to_string(),
+            (Grade::Binary(true), Language::Hebrew) => "עבר".to_string(),
+            (Grade::Binary(false), Language::Hebrew) => "נכשל".to_string(),
+            (Grade::ExemptionWithoutCredit, Language::Hebrew) => "פטור ללא ניקוד".to_string(),
+            (Grade::ExemptionWithCredit, Language::Hebrew) => "פטור עם ניקוד".to_string(),
+            (Grade::NotComplete, Language::Hebrew) => "לא השלים".to_string(),
+            (Grade::Binary(true), Language::English) => "Pass".to_string(),
+            (Grade::Binary(false), Language::English) => "Fail".to_string(),
+            (Grade::ExemptionWithoutCredit, Language::English) => {
+                "Exemption without credit".to_string()
+            }
+            (Grade::ExemptionWithCredit, Language::English) => "Exemption with credit".to_string(),
+            (Grade::NotComplete, Language::English) => "Not complete".to_string(),
+        }
+    }
+
+    // Parses a label in any of the supported languages
+    pub fn from_label(label: &str) -> Option<Grade> {
+        Grade::LABELED
+            .into_iter()
+            .find(|grade| {
+                Language::ALL
+                    .into_iter()
+                    .any(|language| grade.label(language) == label)
+            })
+            .or_else(|| label.parse::<u32>().ok().map(Grade::Numeric))
+    }
+}
+
+// Grades are stored in Hebrew, and rendered in the user's language only when they are displayed
 impl Serialize for Grade {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match *self {
-            Grade::Numeric(grade) => serializer.serialize_str(grade.to_string().as_str()),
-            Grade::Binary(val) if val => serializer.serialize_str("עבר"),
-            Grade::Binary(_) => serializer.serialize_str("נכשל"),
-            Grade::ExemptionWithoutCredit => serializer.serialize_str("פטור ללא ניקוד"),
-            Grade::ExemptionWithCredit => serializer.serialize_str("פטור עם ניקוד"),
-            Grade::NotComplete => serializer.serialize_str("לא השלים"),
-        }
+        serializer.serialize_str(&self.label(locale::serialization_language()))
     }
 }
 
@@ -257,16 +420,9 @@ impl<'de> Visitor<'de> for GradeStrVisitor {
     where
         E: Err,
     {
-        match v {
-            "עבר" => Ok(Grade::Binary(true)),
-            "נכשל" => Ok(Grade::Binary(false)),
-            "פטור ללא ניקוד" => Ok(Grade::ExemptionWithoutCredit),
-            "פטור עם ניקוד" => Ok(Grade::ExemptionWithCredit),
-            "לא השלים" => Ok(Grade::NotComplete),
-            _ if v.parse::<u32>().is_ok() => Ok(Grade::Numeric(
-                v.parse::<u32>().map_err(|e| Err::custom(e.to_string()))?,
-            )),
-            _ => {
+        match Grade::from_label(v) {
+            Some(grade) => Ok(grade),
+            None => {
                 let err: E = Err::invalid_type(Unexpected::Str(v), &self);
                 log::error!("Json deserialize error: {}", err.to_string());
                 Err(err)
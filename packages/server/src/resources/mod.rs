@@ -1,6 +1,10 @@
 pub mod catalog;
+pub mod catalog_file;
+pub mod catalog_version;
 pub mod course;
+pub mod snapshot;
 pub mod user;
+pub mod version;
 
 #[cfg(test)]
 pub mod tests;
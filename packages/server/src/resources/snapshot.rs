@@ -0,0 +1,25 @@
+use bson::{doc, DateTime, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{core::degree_status::DegreeStatus, db::Resource};
+
+// A degree status which was computed for a user, kept so changes in the user's status can be tracked
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DegreeStatusSnapshot {
+    #[serde(rename(serialize = "_id", deserialize = "_id"))]
+    pub id: bson::oid::ObjectId,
+    pub user_id: String,
+    pub timestamp: DateTime,
+    pub catalog_id: bson::oid::ObjectId,
+    pub input_hash: String, // Identifies the course list and catalog the status was computed from
+    pub degree_status: DegreeStatus,
+}
+
+impl Resource for DegreeStatusSnapshot {
+    fn collection_name() -> &'static str {
+        "DegreeStatusHistory"
+    }
+    fn key(&self) -> Document {
+        doc! {"_id": self.id}
+    }
+}
@@ -1,7 +1,15 @@
 use actix_rt::test;
 use serde_json::json;
 
-use super::course::{CourseState, Grade};
+use std::collections::BTreeMap;
+
+use super::catalog::{Catalog, CatalogDetails, EnglishRequirement, Faculty, MedicinePolicy};
+use super::catalog_file::{catalog_from_toml, catalog_to_toml};
+use super::course::{self, Course, CourseBank, CourseState, CourseStatus, Grade};
+use crate::core::{
+    locale::{self, Language},
+    types::{CreditOverflow, Rule, SpecializationGroup, SpecializationGroups},
+};
 
 #[test]
 async fn test_course_state_serde() {
@@ -54,3 +62,232 @@ async fn test_course_grade_serde() {
     assert!(res.is_err());
     assert!(format!("{res:#?}").contains("expected a valid string representation of a grade"));
 }
+
+#[test]
+async fn test_localized_labels() {
+    let course_status = CourseStatus {
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::ExemptionWithCredit),
+        ..Default::default()
+    };
+    let json = locale::serialize_in(Language::English, || json!(course_status));
+    assert_eq!(json["state"], json!("Complete"));
+    assert_eq!(json["grade"], json!("Exemption with credit"));
+
+    // Only typed labels are localized, free text with the same key is left as is
+    let mixed = locale::serialize_in(
+        Language::English,
+        || json!({"state": "הושלם", "course_status": course_status}),
+    );
+    assert_eq!(mixed["state"], json!("הושלם"));
+    assert_eq!(mixed["course_status"]["state"], json!("Complete"));
+    // and anything which is serialized later is in Hebrew again
+    assert_eq!(json!(course_status)["state"], json!("הושלם"));
+
+    // even if the serialization panicked
+    let result = std::panic::catch_unwind(|| {
+        locale::serialize_in(Language::English, || panic!("serialization failed"))
+    });
+    assert!(result.is_err());
+    assert_eq!(json!(course_status)["state"], json!("הושלם"));
+
+    // Labels which were sent back by a client in English are accepted
+    let course_status: CourseStatus = serde_json::from_value(json).expect("Fail to deserialize");
+    assert_eq!(course_status.state, Some(CourseState::Complete));
+    assert_eq!(course_status.grade, Some(Grade::ExemptionWithCredit));
+    // and are stored in Hebrew
+    assert_eq!(json!(course_status)["state"], json!("הושלם"));
+
+    assert_eq!(
+        Language::from_accept_language("fr-FR,en-US;q=0.8,he;q=0.9"),
+        Some(Language::Hebrew)
+    );
+    assert_eq!(
+        Language::from_accept_language("en-US,en;q=0.9"),
+        Some(Language::English)
+    );
+    assert_eq!(Language::from_accept_language("fr"), None);
+}
+
+#[test]
+async fn test_catalog_file_round_trip() {
+    let catalog = Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "מדמח תלת שנתי 2022-2023".to_string(),
+        faculty: Faculty::Medicine,
+        start_year: Some(2022),
+        track_years: Some(3),
+        track_code: Some("מדמח תלת שנתי".to_string()),
+        total_credit: 118.5,
+        description: "a catalog".to_string(),
+        course_banks: vec![
+            CourseBank {
+                name: "חובה".to_string(),
+                rule: Rule::All,
+                credit: Some(84.5),
+            },
+            CourseBank {
+                name: "שרשרת מדעית".to_string(),
+                rule: Rule::Chains(vec![vec!["114051".to_string(), "114052".to_string()]]),
+                credit: Some(8.0),
+            },
+            CourseBank {
+                name: "התמחות".to_string(),
+                rule: Rule::SpecializationGroups(SpecializationGroups {
+                    groups_list: vec![SpecializationGroup {
+                        name: "תורת החישוב".to_string(),
+                        courses_sum: 1,
+                        course_list: vec!["236343".to_string()],
+                        mandatory: Some(vec![vec!["236343".to_string()]]),
+                    }],
+                    groups_number: 1,
+                }),
+                credit: None,
+            },
+            CourseBank {
+                name: "מלג".to_string(),
+                rule: Rule::AccumulateCourses(2),
+                credit: Some(6.0),
+            },
+            CourseBank {
+                name: "בחירה חופשית".to_string(),
+                rule: Rule::Wildcard(true),
+                credit: Some(20.0),
+            },
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "חובה".to_string(),
+            to: "בחירה חופשית".to_string(),
+            cap: Some(2.5),
+            ratio: None,
+        }],
+        course_to_bank: BTreeMap::from([
+            ("104031".to_string(), "חובה".to_string()),
+            ("114051".to_string(), "שרשרת מדעית".to_string()),
+            ("114052".to_string(), "שרשרת מדעית".to_string()),
+            ("236343".to_string(), "התמחות".to_string()),
+        ]),
+        catalog_replacements: BTreeMap::from([
+            ("104031".to_string(), vec!["104166".to_string()]),
+            ("104999".to_string(), vec!["104998".to_string()]),
+        ]),
+        common_replacements: BTreeMap::from([("114051".to_string(), vec!["114071".to_string()])]),
+        english_requirement: Some(EnglishRequirement {
+            level_course: "324033".to_string(),
+            exempt_courses_count: 2,
+            courses_count: 1,
+            from_year: Some(2021),
+            until_year: None,
+        }),
+        medicine_policy: MedicinePolicy {
+            clinical_rotations: vec!["270001".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let toml = catalog_to_toml(catalog.clone()).expect("Fail to export catalog");
+    // The courses and their replacements are written under their banks
+    assert!(toml.contains("[[banks]]"));
+    assert!(toml.contains("[banks.catalog_replacements]"));
+
+    let imported = catalog_from_toml(&toml).expect("Fail to import catalog");
+    assert_eq!(json!(imported), json!(catalog));
+
+    // A catalog without an english requirement is written with an explicit marker
+    let no_english_catalog = Catalog {
+        english_requirement: None,
+        ..catalog.clone()
+    };
+    let toml = catalog_to_toml(no_english_catalog.clone()).expect("Fail to export catalog");
+    assert!(toml.contains("english_requirement = \"none\""));
+    let imported = catalog_from_toml(&toml).expect("Fail to import catalog");
+    assert_eq!(imported.english_requirement, None);
+    // while a file which omits the requirement gets the default one, like a stored catalog
+    let toml = toml.replace("english_requirement = \"none\"\n", "");
+    let imported = catalog_from_toml(&toml).expect("Fail to import catalog");
+    assert_eq!(
+        imported.english_requirement,
+        EnglishRequirement::technion_default()
+    );
+    assert!(catalog_from_toml(&format!("english_requirement = \"nothing\"\n{toml}")).is_err());
+
+    // A catalog with courses of banks which don't exist can't be exported
+    let mut corrupted_catalog = catalog;
+    corrupted_catalog
+        .course_to_bank
+        .insert("104032".to_string(), "unknown".to_string());
+    assert!(catalog_to_toml(corrupted_catalog).is_err());
+}
+
+#[test]
+async fn test_catalog_default_english_requirement() {
+    // A stored catalog without an english requirement gets the same one as a catalog which is built in code
+    let mut json = json!(Catalog::default());
+    json.as_object_mut().unwrap().remove("english_requirement");
+    let catalog: Catalog = serde_json::from_value(json).expect("Fail to deserialize");
+    assert!(catalog.english_requirement.is_some());
+    assert_eq!(
+        catalog.english_requirement,
+        Catalog::default().english_requirement
+    );
+
+    // while a catalog which has no english requirement keeps having none
+    let catalog = Catalog {
+        english_requirement: None,
+        ..Default::default()
+    };
+    let catalog: Catalog = serde_json::from_value(json!(catalog)).expect("Fail to deserialize");
+    assert_eq!(catalog.english_requirement, None);
+}
+
+#[test]
+async fn test_catalog_details() {
+    let catalog = Catalog {
+        name: "מדמח תלת שנתי 2022-2023".to_string(),
+        course_banks: vec![
+            CourseBank {
+                name: "חובה".to_string(),
+                rule: Rule::All,
+                credit: Some(84.5),
+            },
+            CourseBank {
+                name: "רשימה א".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(15.0),
+            },
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "חובה".to_string(),
+            to: "רשימה א".to_string(),
+            cap: None,
+            ratio: None,
+        }],
+        course_to_bank: BTreeMap::from([
+            ("104031".to_string(), "חובה".to_string()),
+            ("236343".to_string(), "רשימה א".to_string()),
+        ]),
+        ..Default::default()
+    };
+    let courses = course::vec_to_map(vec![Course {
+        id: "104031".to_string(),
+        credit: 5.5,
+        name: "חשבון אינפיניטסימלי 1מ'".to_string(),
+        tags: None,
+    }]);
+
+    let details = CatalogDetails::new(catalog, &courses);
+    assert_eq!(details.catalog.course_bank_names, vec!["חובה", "רשימה א"]);
+    assert_eq!(details.credit_overflows.len(), 1);
+    let mandatory = &details.course_banks[0];
+    assert_eq!(mandatory.rule, Rule::All);
+    assert_eq!(mandatory.courses[0].credit, Some(5.5));
+    assert_eq!(
+        mandatory.courses[0].name.as_deref(),
+        Some("חשבון אינפיניטסימלי 1מ'")
+    );
+    // Courses which are not in the courses collection are listed without a name and credit
+    let list_a = &details.course_banks[1];
+    assert_eq!(list_a.courses[0].id, "236343");
+    assert!(list_a.courses[0].name.is_none() && list_a.courses[0].credit.is_none());
+}
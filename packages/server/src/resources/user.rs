@@ -1,6 +1,6 @@
-use super::catalog::DisplayCatalog;
+use super::catalog::{DisplayCatalog, SecondaryCatalog};
 use crate::{
-    core::degree_status::DegreeStatus,
+    core::{degree_status::DegreeStatus, locale::Language},
     db::{Db, Resource},
     error::AppError,
     middleware::auth::Sub,
@@ -13,14 +13,22 @@ use std::{future::Future, pin::Pin};
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct UserDetails {
     pub catalog: Option<DisplayCatalog>,
+    #[serde(default)]
+    pub secondary_catalogs: Vec<SecondaryCatalog>, // For double degrees and minors
     pub degree_status: DegreeStatus,
     pub compute_in_progress: bool,
     pub modified: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_cache_key: Option<String>, // Identifies the input which the degree status was computed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catalog_version: Option<u32>, // A published version of the catalog which the user pinned, if not set the latest version is used
 }
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct UserSettings {
     pub dark_mode: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>, // If not set, the language is negotiated from the request's Accept-Language header
 }
 
 #[derive(Default, Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
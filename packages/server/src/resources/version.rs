@@ -0,0 +1,46 @@
+use bson::{doc, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{Db, Resource},
+    error::AppError,
+};
+
+// A counter of the changes to data which the degree status computation depends on.
+// A cached degree status is valid only as long as the versions of its data didn't change.
+// A counter (rather than the time of the last change) can't repeat itself or go back.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataVersion {
+    #[serde(rename(serialize = "_id", deserialize = "_id"))]
+    pub id: String, // Either "courses" or the id of a catalog
+    #[serde(default)]
+    pub version: i64, // Documents from before the counter was introduced count as version 0
+}
+
+impl Resource for DataVersion {
+    fn collection_name() -> &'static str {
+        "DataVersions"
+    }
+    fn key(&self) -> Document {
+        doc! {"_id": self.id.clone()}
+    }
+}
+
+impl DataVersion {
+    pub const COURSES: &'static str = "courses";
+
+    pub async fn get(db: &Db, id: impl Into<String>) -> Result<i64, AppError> {
+        match db.get::<DataVersion>(id.into()).await {
+            Ok(version) => Ok(version.version),
+            // Data which was never changed since versions were introduced
+            Err(AppError::NotFound(_)) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn bump(db: &Db, id: impl Into<String>) -> Result<(), AppError> {
+        db.increment_field::<DataVersion>(id.into(), "version")
+            .await
+            .map(|_| ())
+    }
+}